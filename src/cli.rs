@@ -0,0 +1,134 @@
+use std::path::PathBuf;
+
+use crate::{error::SnakeError, state::difficulty::Difficulty};
+
+pub const USAGE: &str = "\
+Usage: terminal_snake [OPTIONS]
+
+Options:
+  --difficulty <easy|normal|hard|insane>  How fast the snake moves and speeds up
+  --seed <number>                         Play a repeatable game, where the same
+                                          seed always places the food the same
+  --level <file>                          Play the arena and rules in a level file
+  -h, --help                              Show this help";
+
+/// Settings given on the command line.
+#[derive(Debug, Default, PartialEq)]
+pub struct Options {
+    pub difficulty: Difficulty,
+    // Seeds the random numbers, otherwise they're seeded from the clock.
+    pub seed: Option<u64>,
+    // A level file to play instead of the classic arena.
+    pub level: Option<PathBuf>,
+    pub help: bool,
+}
+
+impl Options {
+    /// Parses the arguments, not including the program name.
+    pub fn parse<I: IntoIterator<Item = String>>(args: I) -> Result<Options, SnakeError> {
+        let mut options = Options::default();
+        let mut args = args.into_iter();
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--difficulty" => {
+                    let value = Options::value(&arg, args.next())?;
+
+                    options.difficulty = match Difficulty::from_name(&value) {
+                        Some(d) => d,
+                        None => {
+                            return Err(SnakeError::new(&format!("Unknown difficulty '{}'", value)))
+                        }
+                    };
+                }
+                "--seed" => {
+                    let value = Options::value(&arg, args.next())?;
+
+                    options.seed = match value.parse() {
+                        Ok(seed) => Some(seed),
+                        Err(_) => {
+                            return Err(SnakeError::new(&format!(
+                                "The seed must be a number from 0 to {}, not '{}'",
+                                u64::MAX,
+                                value
+                            )))
+                        }
+                    };
+                }
+                "--level" => {
+                    options.level = Some(PathBuf::from(Options::value(&arg, args.next())?));
+                }
+                "-h" | "--help" => options.help = true,
+                _ => {
+                    return Err(SnakeError::new(&format!(
+                        "Unknown option '{}'\n\n{}",
+                        arg, USAGE
+                    )))
+                }
+            }
+        }
+
+        Ok(options)
+    }
+
+    fn value(arg: &str, value: Option<String>) -> Result<String, SnakeError> {
+        value.ok_or_else(|| SnakeError::new(&format!("{} needs a value", arg)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Options, SnakeError> {
+        Options::parse(args.iter().map(|a| a.to_string()))
+    }
+
+    #[test]
+    fn parse_defaults() {
+        assert_eq!(parse(&[]), Ok(Options::default()));
+    }
+
+    #[test]
+    fn parse_difficulty() {
+        assert_eq!(
+            parse(&["--difficulty", "insane"]).unwrap().difficulty,
+            Difficulty::Insane
+        );
+    }
+
+    #[test]
+    fn parse_rejects_missing_value() {
+        assert_eq!(
+            parse(&["--difficulty"]),
+            Err(SnakeError::new("--difficulty needs a value"))
+        );
+    }
+
+    #[test]
+    fn parse_rejects_unknown_difficulty() {
+        assert_eq!(
+            parse(&["--difficulty", "meh"]),
+            Err(SnakeError::new("Unknown difficulty 'meh'"))
+        );
+    }
+
+    #[test]
+    fn parse_level() {
+        assert_eq!(
+            parse(&["--level", "levels/garden.txt"]).unwrap().level,
+            Some(PathBuf::from("levels/garden.txt"))
+        );
+    }
+
+    #[test]
+    fn parse_seed() {
+        assert_eq!(parse(&["--seed", "42"]).unwrap().seed, Some(42));
+        assert_eq!(
+            parse(&["--seed", "-1"]),
+            Err(SnakeError::new(
+                "The seed must be a number from 0 to 18446744073709551615, not '-1'"
+            ))
+        );
+    }
+}
@@ -1,5 +1,11 @@
+pub mod clock;
 pub mod coords;
 pub mod graphics;
 pub mod inputhandler;
+pub mod paths;
+pub mod renderer;
+pub mod scene;
 pub mod snengine_error;
+pub mod storage;
 pub mod unicode;
+pub mod widgets;
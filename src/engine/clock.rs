@@ -0,0 +1,291 @@
+use std::cell::Cell;
+use std::time::{Duration, Instant};
+
+// If the game falls this many ticks behind (e.g. the process was stopped),
+// the rest are dropped rather than played back all at once.
+const MAX_TICKS_PER_UPDATE: u32 = 5;
+
+/// Somewhere to read the current time from, as time since a fixed start.
+pub trait TimeSource {
+    fn now(&self) -> Duration;
+}
+
+/// The real, monotonic clock.
+pub struct RealTime {
+    start: Instant,
+}
+
+impl RealTime {
+    pub fn new() -> Self {
+        RealTime {
+            start: Instant::now(),
+        }
+    }
+}
+
+impl Default for RealTime {
+    fn default() -> Self {
+        RealTime::new()
+    }
+}
+
+impl TimeSource for RealTime {
+    fn now(&self) -> Duration {
+        self.start.elapsed()
+    }
+}
+
+/// A clock which only moves when told to, for driving a `GameClock` in tests.
+#[derive(Debug, Default)]
+pub struct FakeTime {
+    now: Cell<Duration>,
+}
+
+impl FakeTime {
+    pub fn new() -> Self {
+        FakeTime::default()
+    }
+
+    pub fn advance(&self, by: Duration) {
+        self.now.set(self.now.get() + by);
+    }
+}
+
+impl TimeSource for FakeTime {
+    fn now(&self) -> Duration {
+        self.now.get()
+    }
+}
+
+/// What the game should do this time round the loop.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Frame {
+    /// How many fixed simulation steps to run.
+    pub ticks: u32,
+    /// Whether it's time to draw.
+    pub render: bool,
+}
+
+/// Fixed timestep game clock.
+///
+/// Real time is added to an accumulator which is spent in whole ticks of
+/// `tick_interval`, so the simulation runs at the same speed however often
+/// the loop comes round. Rendering runs on its own, separate cadence. The
+/// tick interval can be changed at any time, and pausing stops time from
+/// building up in the accumulator.
+pub struct GameClock<T: TimeSource> {
+    time: T,
+    tick_interval: Duration,
+    render_interval: Duration,
+    accumulator: Duration,
+    last_update: Duration,
+    last_render: Option<Duration>,
+    paused: bool,
+}
+
+impl<T: TimeSource> GameClock<T> {
+    pub fn new(time: T, tick_interval: Duration, render_interval: Duration) -> Self {
+        let now = time.now();
+
+        GameClock {
+            time,
+            tick_interval,
+            render_interval,
+            accumulator: Duration::ZERO,
+            last_update: now,
+            last_render: None,
+            paused: false,
+        }
+    }
+
+    /// Moves the clock on to now and works out what needs doing.
+    pub fn update(&mut self) -> Frame {
+        let now = self.time.now();
+        let elapsed = now.saturating_sub(self.last_update);
+        self.last_update = now;
+
+        let mut ticks = 0;
+
+        if !self.paused {
+            self.accumulator += elapsed;
+
+            while self.accumulator >= self.tick_interval {
+                self.accumulator -= self.tick_interval;
+                ticks += 1;
+            }
+
+            if ticks > MAX_TICKS_PER_UPDATE {
+                ticks = MAX_TICKS_PER_UPDATE;
+            }
+        }
+
+        let render = match self.last_render {
+            Some(last) => now.saturating_sub(last) >= self.render_interval,
+            None => true,
+        };
+
+        if render {
+            self.last_render = Some(now);
+        }
+
+        Frame { ticks, render }
+    }
+
+    /// How long until the next tick or render is due, for sleeping.
+    pub fn until_next(&self) -> Duration {
+        let now = self.time.now();
+        let since_update = now.saturating_sub(self.last_update);
+
+        let until_render = match self.last_render {
+            Some(last) => (last + self.render_interval).saturating_sub(now),
+            None => Duration::ZERO,
+        };
+
+        if self.paused {
+            return until_render;
+        }
+
+        let until_tick = self
+            .tick_interval
+            .saturating_sub(self.accumulator + since_update);
+
+        until_tick.min(until_render)
+    }
+
+    pub fn tick_interval(&self) -> Duration {
+        self.tick_interval
+    }
+
+    /// Changes how long a tick lasts from now on. Time already built up
+    /// carries over to the new interval.
+    pub fn set_tick_interval(&mut self, interval: Duration) {
+        self.tick_interval = interval.max(Duration::from_millis(1));
+    }
+
+    pub fn pause(&mut self) {
+        self.paused = true;
+    }
+
+    pub fn resume(&mut self) {
+        if self.paused {
+            // Don't count the time spent paused.
+            self.last_update = self.time.now();
+            self.paused = false;
+        }
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    pub fn time(&self) -> &T {
+        &self.time
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ms(n: u64) -> Duration {
+        Duration::from_millis(n)
+    }
+
+    fn set_clock() -> GameClock<FakeTime> {
+        GameClock::new(FakeTime::new(), ms(100), ms(10))
+    }
+
+    #[test]
+    fn first_update_renders_without_ticking() {
+        let mut clock = set_clock();
+
+        assert_eq!(
+            clock.update(),
+            Frame {
+                ticks: 0,
+                render: true
+            }
+        );
+    }
+
+    #[test]
+    fn ticks_at_fixed_interval() {
+        let mut clock = set_clock();
+        clock.update();
+
+        clock.time().advance(ms(99));
+        assert_eq!(clock.update().ticks, 0);
+
+        clock.time().advance(ms(1));
+        assert_eq!(clock.update().ticks, 1);
+
+        // Left over time is kept in the accumulator.
+        clock.time().advance(ms(250));
+        assert_eq!(clock.update().ticks, 2);
+        clock.time().advance(ms(50));
+        assert_eq!(clock.update().ticks, 1);
+    }
+
+    #[test]
+    fn renders_on_own_cadence() {
+        let mut clock = set_clock();
+        clock.update();
+
+        clock.time().advance(ms(5));
+        assert!(!clock.update().render);
+
+        clock.time().advance(ms(5));
+        assert!(clock.update().render);
+    }
+
+    #[test]
+    fn caps_ticks_when_far_behind() {
+        let mut clock = set_clock();
+        clock.update();
+
+        clock.time().advance(ms(10_000));
+        assert_eq!(clock.update().ticks, MAX_TICKS_PER_UPDATE);
+
+        clock.time().advance(ms(100));
+        assert_eq!(clock.update().ticks, 1);
+    }
+
+    #[test]
+    fn paused_clock_does_not_tick() {
+        let mut clock = set_clock();
+        clock.update();
+        clock.pause();
+
+        clock.time().advance(ms(500));
+        let frame = clock.update();
+        assert_eq!(frame.ticks, 0);
+        assert!(frame.render);
+
+        clock.resume();
+        clock.time().advance(ms(100));
+        assert_eq!(clock.update().ticks, 1);
+    }
+
+    #[test]
+    fn tick_interval_can_change() {
+        let mut clock = set_clock();
+        clock.update();
+        clock.set_tick_interval(ms(50));
+
+        clock.time().advance(ms(100));
+        assert_eq!(clock.update().ticks, 2);
+        assert_eq!(clock.tick_interval(), ms(50));
+    }
+
+    #[test]
+    fn until_next_is_nearest_of_tick_and_render() {
+        let mut clock = set_clock();
+        clock.update();
+
+        clock.time().advance(ms(4));
+        assert_eq!(clock.until_next(), ms(6));
+
+        clock.set_tick_interval(ms(5));
+        assert_eq!(clock.until_next(), ms(1));
+    }
+}
@@ -50,6 +50,58 @@ impl Default for Coords {
     }
 }
 
+/// An area of the terminal, with a 1-based top left corner like `Coords`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rect {
+    pub x: u16,
+    pub y: u16,
+    pub width: u16,
+    pub height: u16,
+}
+
+impl Rect {
+    pub fn new(x: u16, y: u16, width: u16, height: u16) -> Self {
+        Rect {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    /// A `width` x `height` area in the middle of `outer`, shrunk to fit if
+    /// `outer` is smaller.
+    pub fn centered(outer: Rect, width: u16, height: u16) -> Self {
+        let width = width.min(outer.width);
+        let height = height.min(outer.height);
+
+        Rect {
+            x: outer.x + (outer.width - width) / 2,
+            y: outer.y + (outer.height - height) / 2,
+            width,
+            height,
+        }
+    }
+
+    /// The area inside a one cell border.
+    pub fn inner(&self) -> Self {
+        Rect {
+            x: self.x + 1,
+            y: self.y + 1,
+            width: self.width.saturating_sub(2),
+            height: self.height.saturating_sub(2),
+        }
+    }
+
+    pub fn right(&self) -> u16 {
+        self.x + self.width.saturating_sub(1)
+    }
+
+    pub fn bottom(&self) -> u16 {
+        self.y + self.height.saturating_sub(1)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -98,4 +150,20 @@ mod tests {
         let coords = Coords::new(-42, -44);
         assert_eq!(coords.is_active(), false);
     }
+
+    #[test]
+    pub fn rect_centered_in_outer() {
+        let outer = Rect::new(1, 1, 20, 10);
+
+        assert_eq!(Rect::centered(outer, 6, 4), Rect::new(8, 4, 6, 4));
+        assert_eq!(Rect::centered(outer, 30, 4), Rect::new(1, 4, 20, 4));
+    }
+
+    #[test]
+    pub fn rect_inner_and_edges() {
+        let rect = Rect::new(3, 2, 5, 4);
+
+        assert_eq!(rect.inner(), Rect::new(4, 3, 3, 2));
+        assert_eq!((rect.right(), rect.bottom()), (7, 5));
+    }
 }
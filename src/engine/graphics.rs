@@ -1,3 +1,5 @@
+use std::io::Write;
+
 use super::{snengine_error::SnengineError, unicode::Unicode};
 
 pub struct Graphics;
@@ -29,12 +31,21 @@ impl Graphics {
         Ok(())
     }
 
+    pub fn write_styled(x: u16, y: u16, text: &str, style: Style) -> Result<(), SnengineError> {
+        if Graphics::is_valid(x, y)? {
+            print!("\x1b[{};{}f", y, x);
+            print!("{}{}{}", style.sgr(), text, Style::PLAIN.sgr());
+        }
+
+        Ok(())
+    }
+
     pub fn clear_screen() {
         print!("\x1b[H"); // move cursor to top left
         print!("\x1b[2J"); // clear screen
     }
 
-    fn is_valid(x: u16, y: u16) -> Result<bool, SnengineError> {
+    pub(crate) fn is_valid(x: u16, y: u16) -> Result<bool, SnengineError> {
         if x == 0 || y == 0 {
             return Err(SnengineError::new(
                 format!("Cannot draw at {}, {}", x, y).as_str(),
@@ -45,6 +56,265 @@ impl Graphics {
     }
 }
 
+/// One of the eight standard terminal colours.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Color {
+    Black,
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    White,
+}
+
+impl Color {
+    fn code(&self) -> u8 {
+        match self {
+            Color::Black => 30,
+            Color::Red => 31,
+            Color::Green => 32,
+            Color::Yellow => 33,
+            Color::Blue => 34,
+            Color::Magenta => 35,
+            Color::Cyan => 36,
+            Color::White => 37,
+        }
+    }
+}
+
+/// How a cell is drawn. The default is the terminal's own colours.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Style {
+    pub fg: Option<Color>,
+    pub bold: bool,
+    pub reverse: bool,
+}
+
+impl Style {
+    pub const PLAIN: Style = Style {
+        fg: None,
+        bold: false,
+        reverse: false,
+    };
+
+    /// Swapped foreground and background, used to highlight a selection.
+    pub const REVERSE: Style = Style {
+        fg: None,
+        bold: false,
+        reverse: true,
+    };
+
+    pub fn fg(color: Color) -> Self {
+        Style {
+            fg: Some(color),
+            ..Style::PLAIN
+        }
+    }
+
+    /// The escape sequence which switches the terminal to this style,
+    /// starting from a reset so nothing carries over from the last one.
+    pub fn sgr(&self) -> String {
+        let mut out = String::from("\x1b[0");
+
+        if self.bold {
+            out.push_str(";1");
+        }
+
+        if self.reverse {
+            out.push_str(";7");
+        }
+
+        if let Some(color) = self.fg {
+            out.push_str(&format!(";{}", color.code()));
+        }
+
+        out.push('m');
+        out
+    }
+}
+
+/// A single character cell in a `FrameBuffer`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Cell {
+    pub char: char,
+    pub style: Style,
+}
+
+impl Default for Cell {
+    fn default() -> Self {
+        Cell {
+            char: Unicode::Space.to_char(),
+            style: Style::PLAIN,
+        }
+    }
+}
+
+/// Double-buffered frame of `Cell`s.
+///
+/// Drawing goes into the back buffer, which is kept between frames. On
+/// `present` the back buffer is compared against what was last sent to the
+/// terminal (the front buffer) and only the cells that changed are written,
+/// in a single batched write.
+pub struct FrameBuffer {
+    cols: u16,
+    rows: u16,
+    front: Vec<Cell>,
+    back: Vec<Cell>,
+}
+
+impl FrameBuffer {
+    /// Creates a buffer for a terminal of `cols` x `rows`. The terminal is
+    /// assumed to have just been cleared, so both buffers start out blank.
+    pub fn new(cols: u16, rows: u16) -> Self {
+        let size = cols as usize * rows as usize;
+
+        FrameBuffer {
+            cols,
+            rows,
+            front: vec![Cell::default(); size],
+            back: vec![Cell::default(); size],
+        }
+    }
+
+    pub fn size(&self) -> (u16, u16) {
+        (self.cols, self.rows)
+    }
+
+    pub fn draw_char(&mut self, x: u16, y: u16, char: Unicode) -> Result<(), SnengineError> {
+        Graphics::is_valid(x, y)?;
+
+        self.set(x, y, char.to_char(), Style::PLAIN);
+
+        Ok(())
+    }
+
+    pub fn write(&mut self, x: u16, y: u16, text: &str) -> Result<(), SnengineError> {
+        self.write_styled(x, y, text, Style::PLAIN)
+    }
+
+    pub fn write_styled(
+        &mut self,
+        x: u16,
+        y: u16,
+        text: &str,
+        style: Style,
+    ) -> Result<(), SnengineError> {
+        Graphics::is_valid(x, y)?;
+
+        for (i, c) in text.chars().enumerate() {
+            self.set(x.saturating_add(i as u16), y, c, style);
+        }
+
+        Ok(())
+    }
+
+    /// Returns the character in the back buffer at the given 1-based position.
+    pub fn char_at(&self, x: u16, y: u16) -> Option<char> {
+        self.index(x, y).map(|i| self.back[i].char)
+    }
+
+    /// Returns the style in the back buffer at the given 1-based position.
+    pub fn style_at(&self, x: u16, y: u16) -> Option<Style> {
+        self.index(x, y).map(|i| self.back[i].style)
+    }
+
+    /// Blanks the back buffer, ready to draw a whole new frame.
+    pub fn clear(&mut self) {
+        self.back.fill(Cell::default());
+    }
+
+    /// Resizes the buffer to a new terminal size. The back buffer starts
+    /// blank and the whole screen is redrawn on the next `present`, which
+    /// also wipes anything the terminal left behind when it was resized.
+    pub fn resize(&mut self, cols: u16, rows: u16) {
+        *self = FrameBuffer::new(cols, rows);
+        self.invalidate();
+    }
+
+    /// Forgets what is on screen so the next `present` redraws every cell.
+    pub fn invalidate(&mut self) {
+        for cell in self.front.iter_mut() {
+            cell.char = '\0';
+        }
+    }
+
+    /// Builds the escape sequences needed to bring the terminal from the
+    /// front buffer to the back buffer, and marks the back buffer as shown.
+    ///
+    /// Runs of adjacent changed cells on a row share a single cursor move,
+    /// and the style is only switched when it differs from the last cell
+    /// written. The terminal is always left in the plain style.
+    pub fn diff(&mut self) -> String {
+        let mut out = String::new();
+        let mut style = Style::PLAIN;
+
+        for y in 0..self.rows as usize {
+            let mut cursor_here = false;
+
+            for x in 0..self.cols as usize {
+                let i = y * self.cols as usize + x;
+
+                if self.front[i] == self.back[i] {
+                    cursor_here = false;
+                    continue;
+                }
+
+                if !cursor_here {
+                    out.push_str(&format!("\x1b[{};{}f", y + 1, x + 1));
+                    cursor_here = true;
+                }
+
+                if self.back[i].style != style {
+                    style = self.back[i].style;
+                    out.push_str(&style.sgr());
+                }
+
+                out.push(self.back[i].char);
+                self.front[i] = self.back[i];
+            }
+        }
+
+        if style != Style::PLAIN {
+            out.push_str(&Style::PLAIN.sgr());
+        }
+
+        out
+    }
+
+    /// Writes the changes since the last frame to stdout and flushes.
+    pub fn present(&mut self) -> Result<(), SnengineError> {
+        let frame = self.diff();
+
+        if frame.is_empty() {
+            return Ok(());
+        }
+
+        let mut stdout = std::io::stdout().lock();
+
+        stdout
+            .write_all(frame.as_bytes())
+            .and_then(|_| stdout.flush())
+            .map_err(|e| SnengineError::new(e.to_string().as_str()))
+    }
+
+    fn set(&mut self, x: u16, y: u16, char: char, style: Style) {
+        // Anything outside the terminal is clipped.
+        if let Some(i) = self.index(x, y) {
+            self.back[i] = Cell { char, style };
+        }
+    }
+
+    fn index(&self, x: u16, y: u16) -> Option<usize> {
+        if x == 0 || y == 0 || x > self.cols || y > self.rows {
+            return None;
+        }
+
+        Some((y as usize - 1) * self.cols as usize + (x as usize - 1))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -111,4 +381,130 @@ mod tests {
         let expected = Err(SnengineError::new("Cannot draw at 1, 0"));
         assert_eq!(result, expected);
     }
+
+    #[test]
+    pub fn frame_buffer_diff_empty_when_nothing_drawn() {
+        let mut fb = FrameBuffer::new(10, 5);
+        assert_eq!(fb.diff(), "");
+    }
+
+    #[test]
+    pub fn frame_buffer_diff_only_contains_changed_cells() {
+        let mut fb = FrameBuffer::new(10, 5);
+        fb.draw_char(3, 2, Unicode::BoxLightVertical).unwrap();
+        fb.draw_char(7, 4, Unicode::BoxLightHorizontal).unwrap();
+
+        assert_eq!(fb.diff(), "\x1b[2;3f│\x1b[4;7f─");
+    }
+
+    #[test]
+    pub fn frame_buffer_diff_batches_adjacent_cells() {
+        let mut fb = FrameBuffer::new(10, 5);
+        fb.write(2, 1, "abc").unwrap();
+
+        assert_eq!(fb.diff(), "\x1b[1;2fabc");
+    }
+
+    #[test]
+    pub fn frame_buffer_diff_is_empty_after_present() {
+        let mut fb = FrameBuffer::new(10, 5);
+        fb.write(2, 1, "abc").unwrap();
+        fb.diff();
+
+        // Redrawing the same content should produce no output.
+        fb.write(2, 1, "abc").unwrap();
+        assert_eq!(fb.diff(), "");
+    }
+
+    #[test]
+    pub fn frame_buffer_clear_only_sends_cells_that_were_drawn() {
+        let mut fb = FrameBuffer::new(10, 1);
+        fb.write(1, 1, "ab").unwrap();
+        fb.diff();
+
+        fb.clear();
+        fb.write(1, 1, "a").unwrap();
+
+        assert_eq!(fb.diff(), "\x1b[1;2f ");
+    }
+
+    #[test]
+    pub fn frame_buffer_diff_switches_style_only_when_it_changes() {
+        let mut fb = FrameBuffer::new(10, 1);
+        fb.write_styled(1, 1, "ab", Style::REVERSE).unwrap();
+        fb.write_styled(3, 1, "c", Style::fg(Color::Red)).unwrap();
+        fb.write(4, 1, "d").unwrap();
+
+        assert_eq!(fb.diff(), "\x1b[1;1f\x1b[0;7mab\x1b[0;31mc\x1b[0md");
+    }
+
+    #[test]
+    pub fn frame_buffer_diff_resets_style_at_the_end() {
+        let mut fb = FrameBuffer::new(10, 1);
+        fb.write_styled(2, 1, "x", Style::REVERSE).unwrap();
+
+        assert_eq!(fb.diff(), "\x1b[1;2f\x1b[0;7mx\x1b[0m");
+        assert_eq!(fb.style_at(2, 1), Some(Style::REVERSE));
+    }
+
+    #[test]
+    pub fn frame_buffer_diff_sends_style_only_changes() {
+        let mut fb = FrameBuffer::new(10, 1);
+        fb.write(1, 1, "a").unwrap();
+        fb.diff();
+
+        fb.write_styled(1, 1, "a", Style::REVERSE).unwrap();
+
+        assert_eq!(fb.diff(), "\x1b[1;1f\x1b[0;7ma\x1b[0m");
+    }
+
+    #[test]
+    pub fn style_sgr_combines_attributes() {
+        let style = Style {
+            fg: Some(Color::Yellow),
+            bold: true,
+            reverse: true,
+        };
+
+        assert_eq!(style.sgr(), "\x1b[0;1;7;33m");
+        assert_eq!(Style::PLAIN.sgr(), "\x1b[0m");
+    }
+
+    #[test]
+    pub fn frame_buffer_invalidate_redraws_everything() {
+        let mut fb = FrameBuffer::new(3, 1);
+        fb.diff();
+        fb.invalidate();
+
+        assert_eq!(fb.diff(), "\x1b[1;1f   ");
+    }
+
+    #[test]
+    pub fn frame_buffer_resize_clears_and_redraws_everything() {
+        let mut fb = FrameBuffer::new(3, 1);
+        fb.write(1, 1, "abc").unwrap();
+        fb.diff();
+        fb.resize(2, 2);
+
+        assert_eq!(fb.size(), (2, 2));
+        assert_eq!(fb.diff(), "\x1b[1;1f  \x1b[2;1f  ");
+    }
+
+    #[test]
+    pub fn frame_buffer_clips_outside_terminal() {
+        let mut fb = FrameBuffer::new(4, 2);
+        fb.write(3, 2, "xyz").unwrap();
+
+        assert_eq!(fb.char_at(3, 2), Some('x'));
+        assert_eq!(fb.char_at(4, 2), Some('y'));
+        assert_eq!(fb.char_at(5, 2), None);
+    }
+
+    #[test]
+    pub fn frame_buffer_draw_char_error_if_x_0() {
+        let mut fb = FrameBuffer::new(4, 2);
+        let result = fb.draw_char(0, 1, Unicode::Space);
+        let expected = Err(SnengineError::new("Cannot draw at 0, 1"));
+        assert_eq!(result, expected);
+    }
 }
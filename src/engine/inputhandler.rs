@@ -1,72 +1,675 @@
+use std::fmt;
 use std::fs::File;
 use std::io::Read;
+use std::os::unix::io::AsRawFd;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
 
-use crate::Directions;
-use crate::GameState;
+use super::snengine_error::SnengineError;
 
-pub struct InputHandler {}
+/// How long a lone Esc byte waits for the rest of an escape sequence before
+/// it's treated as the Esc key on its own.
+const ESC_TIMEOUT: Duration = Duration::from_millis(50);
 
-impl InputHandler {
-    pub fn handle_input(mut state: GameState, mut file: &File) -> GameState {
-        let mut buffer = [0; 3]; // Buffer to store input characters
-
-        match file.read(&mut buffer) {
-            Ok(0) => state,
-            Ok(_) => {
-                // Left Arrow
-                if buffer[0] == 0x1b && buffer[1] == 0x5b && buffer[2] == 0x44 {
-                    // Stop the player from going back on themselves.
-                    if state.snake.direction == Directions::Right {
-                        return state;
-                    }
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum KeyCode {
+    Char(char),
+    Up,
+    Down,
+    Left,
+    Right,
+    Home,
+    End,
+    PageUp,
+    PageDown,
+    Insert,
+    Delete,
+    F(u8),
+    Enter,
+    Esc,
+    Backspace,
+    Tab,
+}
 
-                    state.snake.direction = Directions::Left;
-                }
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Modifiers {
+    pub shift: bool,
+    pub ctrl: bool,
+    pub alt: bool,
+}
 
-                // Right Arrow
-                if buffer[0] == 0x1b && buffer[1] == 0x5b && buffer[2] == 0x43 {
-                    // Stop the player from going back on themselves.
-                    if state.snake.direction == Directions::Left {
-                        return state;
-                    }
+impl Modifiers {
+    pub const NONE: Modifiers = Modifiers {
+        shift: false,
+        ctrl: false,
+        alt: false,
+    };
 
-                    state.snake.direction = Directions::Right;
-                }
+    pub const CTRL: Modifiers = Modifiers {
+        shift: false,
+        ctrl: true,
+        alt: false,
+    };
+
+    pub const ALT: Modifiers = Modifiers {
+        shift: false,
+        ctrl: false,
+        alt: true,
+    };
+
+    pub const SHIFT: Modifiers = Modifiers {
+        shift: true,
+        ctrl: false,
+        alt: false,
+    };
+
+    //
+    // xterm encodes modifiers in escape sequences as 1 + a bit mask of
+    // Shift (1), Alt (2) and Ctrl (4).
+    //
+    fn from_xterm(param: u16) -> Self {
+        let mask = param.saturating_sub(1);
+
+        Modifiers {
+            shift: mask & 1 != 0,
+            alt: mask & 2 != 0,
+            ctrl: mask & 4 != 0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct KeyEvent {
+    pub code: KeyCode,
+    pub modifiers: Modifiers,
+}
+
+impl KeyEvent {
+    pub fn new(code: KeyCode) -> Self {
+        KeyEvent {
+            code,
+            modifiers: Modifiers::NONE,
+        }
+    }
+
+    pub fn with_modifiers(code: KeyCode, modifiers: Modifiers) -> Self {
+        KeyEvent { code, modifiers }
+    }
+}
+
+impl fmt::Display for KeyEvent {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.modifiers.ctrl {
+            write!(f, "Ctrl+")?;
+        }
+
+        if self.modifiers.alt {
+            write!(f, "Alt+")?;
+        }
+
+        if self.modifiers.shift {
+            write!(f, "Shift+")?;
+        }
+
+        match self.code {
+            KeyCode::Char(' ') => write!(f, "Space"),
+            KeyCode::Char(',') => write!(f, "Comma"),
+            KeyCode::Char(c) => write!(f, "{}", c),
+            KeyCode::F(n) => write!(f, "F{}", n),
+            code => write!(f, "{:?}", code),
+        }
+    }
+}
+
+impl FromStr for KeyEvent {
+    type Err = SnengineError;
+
+    //
+    // Parses key names as written by `Display`, e.g. `Up`, `w`, `Space`,
+    // `F5` or `Ctrl+Left`. Named keys and modifiers are case insensitive,
+    // single characters are not.
+    //
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut modifiers = Modifiers::NONE;
+        let mut name = s.trim();
+
+        loop {
+            let lower = name.to_ascii_lowercase();
+
+            let (flag, len) = if lower.starts_with("ctrl+") {
+                (&mut modifiers.ctrl, 5)
+            } else if lower.starts_with("alt+") {
+                (&mut modifiers.alt, 4)
+            } else if lower.starts_with("shift+") {
+                (&mut modifiers.shift, 6)
+            } else {
+                break;
+            };
 
-                // Up Arrow
-                if buffer[0] == 0x1b && buffer[1] == 0x5b && buffer[2] == 0x41 {
-                    // Stop the player from going back on themselves.
-                    if state.snake.direction == Directions::Down {
-                        return state;
+            // A trailing `+` is the plus key itself, not another modifier.
+            if name.len() == len {
+                break;
+            }
+
+            *flag = true;
+            name = &name[len..];
+        }
+
+        let code = match name.to_ascii_lowercase().as_str() {
+            "up" => KeyCode::Up,
+            "down" => KeyCode::Down,
+            "left" => KeyCode::Left,
+            "right" => KeyCode::Right,
+            "home" => KeyCode::Home,
+            "end" => KeyCode::End,
+            "pageup" => KeyCode::PageUp,
+            "pagedown" => KeyCode::PageDown,
+            "insert" => KeyCode::Insert,
+            "delete" => KeyCode::Delete,
+            "enter" => KeyCode::Enter,
+            "esc" | "escape" => KeyCode::Esc,
+            "backspace" => KeyCode::Backspace,
+            "tab" => KeyCode::Tab,
+            "space" => KeyCode::Char(' '),
+            // Commas separate keys in the bindings file, so it needs a name.
+            "comma" => KeyCode::Char(','),
+            lower => match (lower.strip_prefix('f'), name.chars().count()) {
+                (Some(n), _) if !n.is_empty() && n.chars().all(|c| c.is_ascii_digit()) => {
+                    match n.parse() {
+                        Ok(n @ 1..=12) => KeyCode::F(n),
+                        _ => return Err(SnengineError::new(&format!("Unknown key '{}'", s))),
                     }
+                }
+                (_, 1) => KeyCode::Char(name.chars().next().unwrap_or(' ')),
+                _ => return Err(SnengineError::new(&format!("Unknown key '{}'", s))),
+            },
+        };
+
+        Ok(KeyEvent::with_modifiers(code, modifiers))
+    }
+}
+
+// The result of trying to decode one key from the front of a byte buffer.
+enum Decoded {
+    // A key and how many bytes it used.
+    Key(KeyEvent, usize),
+    // Bytes that don't make up a key we know about and should be dropped.
+    Skip(usize),
+    // The buffer ends part way through a sequence.
+    Incomplete,
+}
+
+/// Turns the raw bytes read from the terminal into `KeyEvent`s.
+///
+/// Sequences split across reads are held until the rest arrives, and a lone
+/// Esc is only reported once `ESC_TIMEOUT` has passed without anything
+/// following it, since it's also the start of every escape sequence.
+#[derive(Debug, Default)]
+pub struct KeyDecoder {
+    pending: Vec<u8>,
+    pending_since: Option<Instant>,
+}
+
+impl KeyDecoder {
+    pub fn new() -> Self {
+        KeyDecoder::default()
+    }
+
+    /// Decodes as many keys as possible from `bytes` plus anything left
+    /// over from previous reads.
+    pub fn feed(&mut self, bytes: &[u8], now: Instant) -> Vec<KeyEvent> {
+        self.pending.extend_from_slice(bytes);
+        let buffered = self.pending.len();
+
+        let keys = self.decode(false);
+
+        // The timeout runs from when whatever is left over started arriving.
+        self.pending_since = match self.pending.len() {
+            0 => None,
+            n if n < buffered => Some(now),
+            _ => self.pending_since.or(Some(now)),
+        };
+
+        keys
+    }
+
+    /// Gives up waiting on an incomplete sequence once it has been pending
+    /// for longer than the Esc timeout, and decodes it as best it can.
+    pub fn flush_timeout(&mut self, now: Instant) -> Vec<KeyEvent> {
+        match self.pending_since {
+            Some(since) if now.duration_since(since) >= ESC_TIMEOUT => {
+                self.pending_since = None;
+                self.decode(true)
+            }
+            _ => Vec::new(),
+        }
+    }
 
-                    state.snake.direction = Directions::Up;
+    /// Whether part of a sequence is waiting for more bytes.
+    pub fn is_pending(&self) -> bool {
+        !self.pending.is_empty()
+    }
+
+    fn decode(&mut self, timed_out: bool) -> Vec<KeyEvent> {
+        let mut keys = Vec::new();
+        let mut start = 0;
+
+        while start < self.pending.len() {
+            match KeyDecoder::decode_one(&self.pending[start..], timed_out) {
+                Decoded::Key(key, used) => {
+                    keys.push(key);
+                    start += used;
                 }
+                Decoded::Skip(used) => start += used,
+                // Nothing more is coming, so the first byte can only be
+                // dropped and the rest decoded on their own.
+                Decoded::Incomplete if timed_out => start += 1,
+                Decoded::Incomplete => break,
+            }
+        }
 
-                // Down Arrow
-                if buffer[0] == 0x1b && buffer[1] == 0x5b && buffer[2] == 0x42 {
-                    // Stop the player from going back on themselves.
-                    if state.snake.direction == Directions::Up {
-                        return state;
-                    }
+        self.pending.drain(..start);
 
-                    state.snake.direction = Directions::Down;
+        keys
+    }
+
+    fn decode_one(bytes: &[u8], timed_out: bool) -> Decoded {
+        match bytes[0] {
+            0x1b => KeyDecoder::decode_escape(bytes, timed_out),
+            b'\r' | b'\n' => Decoded::Key(KeyEvent::new(KeyCode::Enter), 1),
+            b'\t' => Decoded::Key(KeyEvent::new(KeyCode::Tab), 1),
+            0x7f | 0x08 => Decoded::Key(KeyEvent::new(KeyCode::Backspace), 1),
+            0x00 => Decoded::Key(
+                KeyEvent::with_modifiers(KeyCode::Char(' '), Modifiers::CTRL),
+                1,
+            ),
+            // Ctrl+A to Ctrl+Z, minus the ones handled above.
+            b @ 0x01..=0x1a => Decoded::Key(
+                KeyEvent::with_modifiers(KeyCode::Char((b'a' + b - 1) as char), Modifiers::CTRL),
+                1,
+            ),
+            // Ctrl+\ Ctrl+] Ctrl+^ Ctrl+_
+            b @ 0x1c..=0x1f => Decoded::Key(
+                KeyEvent::with_modifiers(KeyCode::Char((b + 0x40) as char), Modifiers::CTRL),
+                1,
+            ),
+            _ => KeyDecoder::decode_utf8(bytes),
+        }
+    }
+
+    fn decode_escape(bytes: &[u8], timed_out: bool) -> Decoded {
+        match bytes.get(1) {
+            None if timed_out => Decoded::Key(KeyEvent::new(KeyCode::Esc), 1),
+            None => Decoded::Incomplete,
+            // Esc pressed twice.
+            Some(0x1b) => Decoded::Key(KeyEvent::new(KeyCode::Esc), 1),
+            // An unfinished sequence was Alt+[ followed by ordinary keys.
+            Some(b'[') => match KeyDecoder::decode_csi(bytes) {
+                Decoded::Incomplete if timed_out => Decoded::Key(
+                    KeyEvent::with_modifiers(KeyCode::Char('['), Modifiers::ALT),
+                    2,
+                ),
+                decoded => decoded,
+            },
+            Some(b'O') => match bytes.get(2) {
+                None if timed_out => Decoded::Key(
+                    KeyEvent::with_modifiers(KeyCode::Char('O'), Modifiers::ALT),
+                    2,
+                ),
+                None => Decoded::Incomplete,
+                Some(&b) => match KeyDecoder::final_to_code(b) {
+                    Some(code) => Decoded::Key(KeyEvent::new(code), 3),
+                    None => Decoded::Skip(3),
+                },
+            },
+            // Alt sends Esc followed by the key.
+            Some(_) => match KeyDecoder::decode_one(&bytes[1..], timed_out) {
+                Decoded::Key(mut key, used) => {
+                    key.modifiers.alt = true;
+                    Decoded::Key(key, used + 1)
                 }
+                Decoded::Skip(used) => Decoded::Skip(used + 1),
+                Decoded::Incomplete => Decoded::Incomplete,
+            },
+        }
+    }
 
-                state
+    //
+    // Control Sequence Introducer: Esc [ <params> <final byte>, e.g. Esc [ A
+    // for Up or Esc [ 1 ; 5 C for Ctrl+Right.
+    //
+    fn decode_csi(bytes: &[u8]) -> Decoded {
+        let end = match bytes[2..].iter().position(|b| (0x40..=0x7e).contains(b)) {
+            Some(i) => i + 2,
+            None => return Decoded::Incomplete,
+        };
+
+        let params: Vec<u16> = String::from_utf8_lossy(&bytes[2..end])
+            .split(';')
+            .map(|p| p.parse().unwrap_or(1))
+            .collect();
+
+        let modifiers = Modifiers::from_xterm(params.get(1).copied().unwrap_or(1));
+
+        let code = match bytes[end] {
+            b'~' => match params[0] {
+                1 | 7 => Some(KeyCode::Home),
+                2 => Some(KeyCode::Insert),
+                3 => Some(KeyCode::Delete),
+                4 | 8 => Some(KeyCode::End),
+                5 => Some(KeyCode::PageUp),
+                6 => Some(KeyCode::PageDown),
+                n @ 11..=15 => Some(KeyCode::F((n - 10) as u8)),
+                n @ 17..=21 => Some(KeyCode::F((n - 11) as u8)),
+                n @ 23..=24 => Some(KeyCode::F((n - 12) as u8)),
+                _ => None,
+            },
+            b'Z' => {
+                return Decoded::Key(
+                    KeyEvent::with_modifiers(KeyCode::Tab, Modifiers::SHIFT),
+                    end + 1,
+                )
+            }
+            b => KeyDecoder::final_to_code(b),
+        };
+
+        match code {
+            Some(code) => Decoded::Key(KeyEvent::with_modifiers(code, modifiers), end + 1),
+            None => Decoded::Skip(end + 1),
+        }
+    }
+
+    // Final bytes shared by CSI and SS3 (Esc O) sequences.
+    fn final_to_code(b: u8) -> Option<KeyCode> {
+        match b {
+            b'A' => Some(KeyCode::Up),
+            b'B' => Some(KeyCode::Down),
+            b'C' => Some(KeyCode::Right),
+            b'D' => Some(KeyCode::Left),
+            b'H' => Some(KeyCode::Home),
+            b'F' => Some(KeyCode::End),
+            b'P' => Some(KeyCode::F(1)),
+            b'Q' => Some(KeyCode::F(2)),
+            b'R' => Some(KeyCode::F(3)),
+            b'S' => Some(KeyCode::F(4)),
+            _ => None,
+        }
+    }
+
+    fn decode_utf8(bytes: &[u8]) -> Decoded {
+        let len = match bytes[0] {
+            0x00..=0x7f => 1,
+            0xc0..=0xdf => 2,
+            0xe0..=0xef => 3,
+            0xf0..=0xf7 => 4,
+            _ => return Decoded::Skip(1),
+        };
+
+        if bytes.len() < len {
+            return Decoded::Incomplete;
+        }
+
+        match std::str::from_utf8(&bytes[..len]) {
+            Ok(s) => match s.chars().next() {
+                Some(c) => Decoded::Key(KeyEvent::new(KeyCode::Char(c)), len),
+                None => Decoded::Skip(len),
+            },
+            Err(_) => Decoded::Skip(1),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct InputHandler {
+    decoder: KeyDecoder,
+}
+
+impl InputHandler {
+    pub fn new() -> Self {
+        InputHandler::default()
+    }
+
+    /// Reads everything waiting on the (non-blocking) input and returns the
+    /// keys it decodes to, in the order they were pressed.
+    pub fn read_keys(&mut self, mut file: &File) -> Vec<KeyEvent> {
+        let mut buffer = [0; 64]; // Buffer to store input characters
+        let mut keys = Vec::new();
+
+        loop {
+            match file.read(&mut buffer) {
+                Ok(0) => break,
+                Ok(n) => keys.extend(self.decoder.feed(&buffer[..n], Instant::now())),
+                Err(_) => break,
             }
-            Err(_) => state,
+        }
+
+        keys.extend(self.decoder.flush_timeout(Instant::now()));
+
+        keys
+    }
+
+    /// Sleeps until there is input to read, a signal arrives or `timeout`
+    /// passes. `None` waits indefinitely, unless a lone Esc is waiting to
+    /// time out.
+    pub fn wait(&self, file: &File, timeout: Option<Duration>) {
+        let timeout = match (timeout, self.decoder.is_pending()) {
+            (Some(t), true) => Some(t.min(ESC_TIMEOUT)),
+            (None, true) => Some(ESC_TIMEOUT),
+            (t, false) => t,
+        };
+
+        let mut fds = libc::pollfd {
+            fd: file.as_raw_fd(),
+            events: libc::POLLIN,
+            revents: 0,
+        };
+
+        let ms = match timeout {
+            // Round up so we don't wake just before something is due.
+            Some(t) => t.as_micros().div_ceil(1000).min(i32::MAX as u128) as i32,
+            None => -1,
+        };
+
+        unsafe {
+            libc::poll(&mut fds, 1, ms);
         }
     }
 }
 
-/*
- * There is now some game logic in the above as we are stopping the user from
- * going back on themselves.  This logic should be moved out of here into a
- * game input handler which performs these checks and does whatever is
- * necessary. That way the engine stays clean.
- *
- * Additionally, a cool Becca feature: instead of stopping user going back on
- * themselves, if they do, could the snake just loop back on itself. Would
- * it go up on Y or down on Y when it backtracks left and right...?
-*/
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn feed(bytes: &[u8]) -> Vec<KeyEvent> {
+        KeyDecoder::new().feed(bytes, Instant::now())
+    }
+
+    fn key(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code)
+    }
+
+    #[test]
+    fn decodes_plain_chars() {
+        assert_eq!(
+            feed(b"wA1"),
+            vec![
+                key(KeyCode::Char('w')),
+                key(KeyCode::Char('A')),
+                key(KeyCode::Char('1'))
+            ]
+        );
+    }
+
+    #[test]
+    fn decodes_utf8_char() {
+        assert_eq!(feed("é".as_bytes()), vec![key(KeyCode::Char('é'))]);
+    }
+
+    #[test]
+    fn decodes_arrows_in_normal_and_application_mode() {
+        assert_eq!(
+            feed(b"\x1b[A\x1b[B\x1bOC\x1bOD"),
+            vec![
+                key(KeyCode::Up),
+                key(KeyCode::Down),
+                key(KeyCode::Right),
+                key(KeyCode::Left)
+            ]
+        );
+    }
+
+    #[test]
+    fn decodes_modified_arrow() {
+        assert_eq!(
+            feed(b"\x1b[1;5C"),
+            vec![KeyEvent::with_modifiers(KeyCode::Right, Modifiers::CTRL)]
+        );
+        assert_eq!(
+            feed(b"\x1b[1;2A"),
+            vec![KeyEvent::with_modifiers(KeyCode::Up, Modifiers::SHIFT)]
+        );
+    }
+
+    #[test]
+    fn decodes_function_and_editing_keys() {
+        assert_eq!(
+            feed(b"\x1bOP\x1b[15~\x1b[24~\x1b[3~\x1b[5~\x1b[H"),
+            vec![
+                key(KeyCode::F(1)),
+                key(KeyCode::F(5)),
+                key(KeyCode::F(12)),
+                key(KeyCode::Delete),
+                key(KeyCode::PageUp),
+                key(KeyCode::Home)
+            ]
+        );
+    }
+
+    #[test]
+    fn decodes_control_keys() {
+        assert_eq!(
+            feed(b"\r\x7f\t\x03"),
+            vec![
+                key(KeyCode::Enter),
+                key(KeyCode::Backspace),
+                key(KeyCode::Tab),
+                KeyEvent::with_modifiers(KeyCode::Char('c'), Modifiers::CTRL)
+            ]
+        );
+    }
+
+    #[test]
+    fn decodes_alt_char() {
+        assert_eq!(
+            feed(b"\x1bx"),
+            vec![KeyEvent::with_modifiers(KeyCode::Char('x'), Modifiers::ALT)]
+        );
+    }
+
+    #[test]
+    fn holds_sequence_split_across_reads() {
+        let mut decoder = KeyDecoder::new();
+        let now = Instant::now();
+
+        assert_eq!(decoder.feed(b"a\x1b", now), vec![key(KeyCode::Char('a'))]);
+        assert_eq!(decoder.feed(b"[", now), vec![]);
+        assert_eq!(decoder.feed(b"D", now), vec![key(KeyCode::Left)]);
+    }
+
+    #[test]
+    fn lone_esc_is_reported_after_timeout() {
+        let mut decoder = KeyDecoder::new();
+        let now = Instant::now();
+
+        assert_eq!(decoder.feed(b"\x1b", now), vec![]);
+        assert_eq!(decoder.flush_timeout(now), vec![]);
+        assert_eq!(
+            decoder.flush_timeout(now + ESC_TIMEOUT),
+            vec![key(KeyCode::Esc)]
+        );
+        assert_eq!(decoder.flush_timeout(now + ESC_TIMEOUT * 2), vec![]);
+    }
+
+    #[test]
+    fn unfinished_sequence_is_decoded_after_timeout() {
+        let mut decoder = KeyDecoder::new();
+        let now = Instant::now();
+
+        assert_eq!(decoder.feed(b"\x1b[", now), vec![]);
+        assert_eq!(
+            decoder.flush_timeout(now + ESC_TIMEOUT),
+            vec![KeyEvent::with_modifiers(KeyCode::Char('['), Modifiers::ALT)]
+        );
+
+        assert_eq!(decoder.feed(b"\x1b[1;5", now), vec![]);
+        assert_eq!(
+            decoder.flush_timeout(now + ESC_TIMEOUT),
+            vec![
+                KeyEvent::with_modifiers(KeyCode::Char('['), Modifiers::ALT),
+                key(KeyCode::Char('1')),
+                key(KeyCode::Char(';')),
+                key(KeyCode::Char('5'))
+            ]
+        );
+        assert!(!decoder.is_pending());
+    }
+
+    #[test]
+    fn skips_unknown_sequence() {
+        assert_eq!(feed(b"\x1b[99~q"), vec![key(KeyCode::Char('q'))]);
+    }
+
+    #[test]
+    fn parses_key_names() {
+        assert_eq!("Up".parse(), Ok(key(KeyCode::Up)));
+        assert_eq!("space".parse(), Ok(key(KeyCode::Char(' '))));
+        assert_eq!("W".parse(), Ok(key(KeyCode::Char('W'))));
+        assert_eq!("f11".parse(), Ok(key(KeyCode::F(11))));
+        assert_eq!("f".parse(), Ok(key(KeyCode::Char('f'))));
+        assert_eq!(
+            "Ctrl+Alt+x".parse(),
+            Ok(KeyEvent::with_modifiers(
+                KeyCode::Char('x'),
+                Modifiers {
+                    shift: false,
+                    ctrl: true,
+                    alt: true
+                }
+            ))
+        );
+        assert_eq!(
+            "Ctrl++".parse(),
+            Ok(KeyEvent::with_modifiers(
+                KeyCode::Char('+'),
+                Modifiers::CTRL
+            ))
+        );
+    }
+
+    #[test]
+    fn parse_rejects_unknown_key() {
+        assert_eq!(
+            "Jump".parse::<KeyEvent>(),
+            Err(SnengineError::new("Unknown key 'Jump'"))
+        );
+        assert_eq!(
+            "F13".parse::<KeyEvent>(),
+            Err(SnengineError::new("Unknown key 'F13'"))
+        );
+    }
+
+    #[test]
+    fn display_round_trips() {
+        for name in [
+            "Up",
+            "Space",
+            "Comma",
+            "w",
+            "F5",
+            "Ctrl+Left",
+            "Alt+Shift+Tab",
+        ] {
+            assert_eq!(name.parse::<KeyEvent>().unwrap().to_string(), name);
+        }
+    }
+}
@@ -0,0 +1,29 @@
+use std::env;
+use std::path::PathBuf;
+
+/// Where an application should keep its files, following the XDG base
+/// directory spec.
+pub struct Paths;
+
+impl Paths {
+    /// `$XDG_CONFIG_HOME/<app>`, falling back to `~/.config/<app>`.
+    pub fn config_dir(app: &str) -> Option<PathBuf> {
+        Paths::base_dir("XDG_CONFIG_HOME", ".config").map(|d| d.join(app))
+    }
+
+    /// `$XDG_DATA_HOME/<app>`, falling back to `~/.local/share/<app>`.
+    pub fn data_dir(app: &str) -> Option<PathBuf> {
+        Paths::base_dir("XDG_DATA_HOME", ".local/share").map(|d| d.join(app))
+    }
+
+    fn base_dir(var: &str, home_fallback: &str) -> Option<PathBuf> {
+        // The spec says relative paths in these variables should be ignored.
+        match env::var_os(var).map(PathBuf::from) {
+            Some(dir) if dir.is_absolute() => Some(dir),
+            _ => env::var_os("HOME")
+                .map(PathBuf::from)
+                .filter(|home| home.is_absolute())
+                .map(|home| home.join(home_fallback)),
+        }
+    }
+}
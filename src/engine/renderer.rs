@@ -0,0 +1,235 @@
+use std::io::Write;
+
+use crate::terminal::terminal::Terminal;
+
+use super::{
+    graphics::{Cell, FrameBuffer, Graphics, Style},
+    snengine_error::SnengineError,
+    unicode::Unicode,
+};
+
+/// Something the game can draw onto, addressed with 1-based terminal
+/// coordinates like the ANSI cursor.
+pub trait Renderer {
+    fn draw_char(&mut self, x: u16, y: u16, char: Unicode) -> Result<(), SnengineError>;
+
+    fn write(&mut self, x: u16, y: u16, text: &str) -> Result<(), SnengineError>;
+
+    fn write_styled(
+        &mut self,
+        x: u16,
+        y: u16,
+        text: &str,
+        style: Style,
+    ) -> Result<(), SnengineError>;
+
+    /// Makes everything drawn so far visible.
+    fn present(&mut self) -> Result<(), SnengineError>;
+
+    /// Columns and rows available to draw in.
+    fn size(&self) -> (u16, u16);
+}
+
+impl Renderer for Graphics {
+    fn draw_char(&mut self, x: u16, y: u16, char: Unicode) -> Result<(), SnengineError> {
+        Graphics::draw_char(x, y, char)
+    }
+
+    fn write(&mut self, x: u16, y: u16, text: &str) -> Result<(), SnengineError> {
+        Graphics::write(x, y, text)
+    }
+
+    fn write_styled(
+        &mut self,
+        x: u16,
+        y: u16,
+        text: &str,
+        style: Style,
+    ) -> Result<(), SnengineError> {
+        Graphics::write_styled(x, y, text, style)
+    }
+
+    fn present(&mut self) -> Result<(), SnengineError> {
+        std::io::stdout()
+            .flush()
+            .map_err(|e| SnengineError::new(e.to_string().as_str()))
+    }
+
+    fn size(&self) -> (u16, u16) {
+        Terminal::get_console_size()
+    }
+}
+
+impl Renderer for FrameBuffer {
+    fn draw_char(&mut self, x: u16, y: u16, char: Unicode) -> Result<(), SnengineError> {
+        FrameBuffer::draw_char(self, x, y, char)
+    }
+
+    fn write(&mut self, x: u16, y: u16, text: &str) -> Result<(), SnengineError> {
+        FrameBuffer::write(self, x, y, text)
+    }
+
+    fn write_styled(
+        &mut self,
+        x: u16,
+        y: u16,
+        text: &str,
+        style: Style,
+    ) -> Result<(), SnengineError> {
+        FrameBuffer::write_styled(self, x, y, text, style)
+    }
+
+    fn present(&mut self) -> Result<(), SnengineError> {
+        FrameBuffer::present(self)
+    }
+
+    fn size(&self) -> (u16, u16) {
+        FrameBuffer::size(self)
+    }
+}
+
+/// A renderer which records cells into an in-memory grid instead of a
+/// terminal, so whole frames can be asserted against in tests.
+pub struct HeadlessRenderer {
+    cols: u16,
+    rows: u16,
+    cells: Vec<Cell>,
+    presents: usize,
+}
+
+impl HeadlessRenderer {
+    pub fn new(cols: u16, rows: u16) -> Self {
+        HeadlessRenderer {
+            cols,
+            rows,
+            cells: vec![Cell::default(); cols as usize * rows as usize],
+            presents: 0,
+        }
+    }
+
+    /// Returns the character at the given 1-based position, or `None` if it
+    /// is outside the grid.
+    pub fn char_at(&self, x: u16, y: u16) -> Option<char> {
+        self.index(x, y).map(|i| self.cells[i].char)
+    }
+
+    /// Returns the style at the given 1-based position.
+    pub fn style_at(&self, x: u16, y: u16) -> Option<Style> {
+        self.index(x, y).map(|i| self.cells[i].style)
+    }
+
+    /// Returns the full 1-based row `y`, including trailing spaces.
+    pub fn row(&self, y: u16) -> String {
+        (1..=self.cols).filter_map(|x| self.char_at(x, y)).collect()
+    }
+
+    /// Returns every row joined by newlines, with trailing spaces removed
+    /// from each row so snapshots stay readable.
+    pub fn snapshot(&self) -> String {
+        (1..=self.rows)
+            .map(|y| self.row(y).trim_end().to_string())
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+
+    /// How many times `present` has been called.
+    pub fn presents(&self) -> usize {
+        self.presents
+    }
+
+    fn index(&self, x: u16, y: u16) -> Option<usize> {
+        if x == 0 || y == 0 || x > self.cols || y > self.rows {
+            return None;
+        }
+
+        Some((y as usize - 1) * self.cols as usize + (x as usize - 1))
+    }
+}
+
+impl Renderer for HeadlessRenderer {
+    fn draw_char(&mut self, x: u16, y: u16, char: Unicode) -> Result<(), SnengineError> {
+        Graphics::is_valid(x, y)?;
+
+        if let Some(i) = self.index(x, y) {
+            self.cells[i] = Cell {
+                char: char.to_char(),
+                style: Style::PLAIN,
+            };
+        }
+
+        Ok(())
+    }
+
+    fn write(&mut self, x: u16, y: u16, text: &str) -> Result<(), SnengineError> {
+        self.write_styled(x, y, text, Style::PLAIN)
+    }
+
+    fn write_styled(
+        &mut self,
+        x: u16,
+        y: u16,
+        text: &str,
+        style: Style,
+    ) -> Result<(), SnengineError> {
+        Graphics::is_valid(x, y)?;
+
+        for (i, c) in text.chars().enumerate() {
+            if let Some(i) = self.index(x.saturating_add(i as u16), y) {
+                self.cells[i] = Cell { char: c, style };
+            }
+        }
+
+        Ok(())
+    }
+
+    fn present(&mut self) -> Result<(), SnengineError> {
+        self.presents += 1;
+
+        Ok(())
+    }
+
+    fn size(&self) -> (u16, u16) {
+        (self.cols, self.rows)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn headless_records_chars() {
+        let mut r = HeadlessRenderer::new(5, 2);
+        r.draw_char(2, 1, Unicode::BoxLightVertical).unwrap();
+        r.write(1, 2, "hey").unwrap();
+
+        assert_eq!(r.snapshot(), " │\nhey");
+    }
+
+    #[test]
+    pub fn headless_clips_outside_grid() {
+        let mut r = HeadlessRenderer::new(3, 1);
+        r.write(2, 1, "abc").unwrap();
+        r.draw_char(9, 9, Unicode::BoxLightVertical).unwrap();
+
+        assert_eq!(r.row(1), " ab");
+    }
+
+    #[test]
+    pub fn headless_records_styles() {
+        let mut r = HeadlessRenderer::new(3, 1);
+        r.write_styled(2, 1, "ab", Style::REVERSE).unwrap();
+
+        assert_eq!(r.row(1), " ab");
+        assert_eq!(r.style_at(1, 1), Some(Style::PLAIN));
+        assert_eq!(r.style_at(3, 1), Some(Style::REVERSE));
+    }
+
+    #[test]
+    pub fn headless_error_if_x_0() {
+        let mut r = HeadlessRenderer::new(3, 1);
+        let result = r.draw_char(0, 1, Unicode::Space);
+        let expected = Err(SnengineError::new("Cannot draw at 0, 1"));
+        assert_eq!(result, expected);
+    }
+}
@@ -0,0 +1,276 @@
+use std::error::Error;
+use std::time::Duration;
+
+use crate::terminal::terminal::{Terminal, TerminalEvent, TerminalSession};
+
+use super::{
+    graphics::FrameBuffer,
+    inputhandler::{InputHandler, KeyEvent},
+    renderer::Renderer,
+};
+
+/// What a scene wants to happen next.
+pub enum Transition<C> {
+    /// Stay on this scene.
+    None,
+    /// Put a scene on top of this one, e.g. a settings screen.
+    Push(Box<dyn Scene<C>>),
+    /// Go back to the scene underneath.
+    Pop,
+    /// Replace this scene with another.
+    Switch(Box<dyn Scene<C>>),
+    /// Leave the program.
+    Quit,
+}
+
+/// One screen of the program, such as the title menu or the game itself.
+///
+/// `C` is whatever context the program shares between its scenes. Each
+/// render starts from a blank frame, so scenes draw everything they show.
+pub trait Scene<C> {
+    fn handle_key(&mut self, ctx: &mut C, key: KeyEvent) -> Result<Transition<C>, Box<dyn Error>>;
+
+    /// Called every time round the loop, after input has been handled.
+    fn update(&mut self, _ctx: &mut C) -> Result<Transition<C>, Box<dyn Error>> {
+        Ok(Transition::None)
+    }
+
+    fn render(&mut self, ctx: &C, r: &mut dyn Renderer) -> Result<(), Box<dyn Error>>;
+
+    /// Called when the terminal changes size.
+    fn resize(&mut self, _ctx: &mut C, _cols: u16, _rows: u16) -> Result<(), Box<dyn Error>> {
+        Ok(())
+    }
+
+    /// How long the loop may wait for input before `update` is needed
+    /// again. `None` waits for as long as it takes, so menus don't spin.
+    fn wake_after(&self) -> Option<Duration> {
+        None
+    }
+
+    /// Whether anything has changed which needs drawing.
+    fn needs_render(&self) -> bool {
+        true
+    }
+}
+
+/// A stack of scenes, where the top one gets the input and is drawn.
+pub struct SceneMachine<C> {
+    stack: Vec<Box<dyn Scene<C>>>,
+}
+
+impl<C> SceneMachine<C> {
+    pub fn new(first: Box<dyn Scene<C>>) -> Self {
+        SceneMachine { stack: vec![first] }
+    }
+
+    pub fn is_running(&self) -> bool {
+        !self.stack.is_empty()
+    }
+
+    /// How many scenes are on the stack.
+    pub fn depth(&self) -> usize {
+        self.stack.len()
+    }
+
+    pub fn handle_key(&mut self, ctx: &mut C, key: KeyEvent) -> Result<(), Box<dyn Error>> {
+        if let Some(scene) = self.stack.last_mut() {
+            let transition = scene.handle_key(ctx, key)?;
+            self.apply(transition);
+        }
+
+        Ok(())
+    }
+
+    pub fn update(&mut self, ctx: &mut C) -> Result<(), Box<dyn Error>> {
+        if let Some(scene) = self.stack.last_mut() {
+            let transition = scene.update(ctx)?;
+            self.apply(transition);
+        }
+
+        Ok(())
+    }
+
+    pub fn render(&mut self, ctx: &C, r: &mut dyn Renderer) -> Result<(), Box<dyn Error>> {
+        match self.stack.last_mut() {
+            Some(scene) => scene.render(ctx, r),
+            None => Ok(()),
+        }
+    }
+
+    /// Tells every scene, not just the top one, so they're all laid out
+    /// right when they're shown again.
+    pub fn resize(&mut self, ctx: &mut C, cols: u16, rows: u16) -> Result<(), Box<dyn Error>> {
+        for scene in self.stack.iter_mut() {
+            scene.resize(ctx, cols, rows)?;
+        }
+
+        Ok(())
+    }
+
+    pub fn wake_after(&self) -> Option<Duration> {
+        self.stack.last().and_then(|s| s.wake_after())
+    }
+
+    pub fn needs_render(&self) -> bool {
+        self.stack.last().is_some_and(|s| s.needs_render())
+    }
+
+    /// Runs scenes in the terminal until one of them quits or the stack is
+    /// empty. The session is shared by every scene, so moving between them
+    /// never leaves raw mode or the alternate screen.
+    pub fn run(&mut self, ctx: &mut C, session: &TerminalSession) -> Result<(), Box<dyn Error>> {
+        let (cols, rows) = Terminal::get_console_size();
+        let mut fb = FrameBuffer::new(cols, rows);
+        let mut input = InputHandler::new();
+
+        // Nothing is known about what's on screen yet.
+        fb.invalidate();
+
+        while self.is_running() {
+            if let Some(TerminalEvent::Resized { cols, rows }) = session.poll_event() {
+                fb.resize(cols, rows);
+                self.resize(ctx, cols, rows)?;
+            }
+
+            for key in input.read_keys(session.input()) {
+                if !self.is_running() {
+                    break;
+                }
+
+                self.handle_key(ctx, key)?;
+            }
+
+            if !self.is_running() {
+                break;
+            }
+
+            self.update(ctx)?;
+
+            if !self.is_running() {
+                break;
+            }
+
+            if self.needs_render() {
+                fb.clear();
+                self.render(ctx, &mut fb)?;
+                fb.present()?;
+            }
+
+            input.wait(session.input(), self.wake_after());
+        }
+
+        Ok(())
+    }
+
+    fn apply(&mut self, transition: Transition<C>) {
+        match transition {
+            Transition::None => {}
+            Transition::Push(scene) => self.stack.push(scene),
+            Transition::Pop => {
+                self.stack.pop();
+            }
+            Transition::Switch(scene) => {
+                self.stack.pop();
+                self.stack.push(scene);
+            }
+            Transition::Quit => self.stack.clear(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{inputhandler::KeyCode, renderer::HeadlessRenderer};
+
+    //
+    // A scene which writes its name and turns keys into transitions:
+    // p pushes, o pops, s switches and q quits.
+    //
+    struct Named(&'static str);
+
+    impl Scene<Vec<String>> for Named {
+        fn handle_key(
+            &mut self,
+            ctx: &mut Vec<String>,
+            key: KeyEvent,
+        ) -> Result<Transition<Vec<String>>, Box<dyn Error>> {
+            ctx.push(format!("{} {}", self.0, key));
+
+            Ok(match key.code {
+                KeyCode::Char('p') => Transition::Push(Box::new(Named("pushed"))),
+                KeyCode::Char('o') => Transition::Pop,
+                KeyCode::Char('s') => Transition::Switch(Box::new(Named("switched"))),
+                KeyCode::Char('q') => Transition::Quit,
+                _ => Transition::None,
+            })
+        }
+
+        fn render(
+            &mut self,
+            _ctx: &Vec<String>,
+            r: &mut dyn Renderer,
+        ) -> Result<(), Box<dyn Error>> {
+            r.write(1, 1, self.0)?;
+
+            Ok(())
+        }
+    }
+
+    fn key(c: char) -> KeyEvent {
+        KeyEvent::new(KeyCode::Char(c))
+    }
+
+    fn rendered(machine: &mut SceneMachine<Vec<String>>) -> String {
+        let mut r = HeadlessRenderer::new(10, 1);
+        machine.render(&Vec::new(), &mut r).unwrap();
+
+        r.snapshot()
+    }
+
+    #[test]
+    pub fn push_and_pop_return_to_the_scene_underneath() {
+        let mut ctx = Vec::new();
+        let mut machine = SceneMachine::new(Box::new(Named("title")));
+
+        machine.handle_key(&mut ctx, key('p')).unwrap();
+        assert_eq!(machine.depth(), 2);
+        assert_eq!(rendered(&mut machine), "pushed");
+
+        machine.handle_key(&mut ctx, key('o')).unwrap();
+        assert_eq!(machine.depth(), 1);
+        assert_eq!(rendered(&mut machine), "title");
+        assert_eq!(ctx, vec!["title p", "pushed o"]);
+    }
+
+    #[test]
+    pub fn switch_replaces_only_the_top_scene() {
+        let mut ctx = Vec::new();
+        let mut machine = SceneMachine::new(Box::new(Named("title")));
+
+        machine.handle_key(&mut ctx, key('p')).unwrap();
+        machine.handle_key(&mut ctx, key('s')).unwrap();
+        assert_eq!(machine.depth(), 2);
+        assert_eq!(rendered(&mut machine), "switched");
+
+        machine.handle_key(&mut ctx, key('o')).unwrap();
+        assert_eq!(rendered(&mut machine), "title");
+    }
+
+    #[test]
+    pub fn quit_empties_the_stack() {
+        let mut ctx = Vec::new();
+        let mut machine = SceneMachine::new(Box::new(Named("title")));
+
+        machine.handle_key(&mut ctx, key('p')).unwrap();
+        machine.handle_key(&mut ctx, key('q')).unwrap();
+
+        assert!(!machine.is_running());
+        assert_eq!(rendered(&mut machine), "");
+
+        // Keys after quitting go nowhere.
+        machine.handle_key(&mut ctx, key('x')).unwrap();
+        assert_eq!(ctx.len(), 2);
+    }
+}
@@ -0,0 +1,239 @@
+use std::fmt;
+use std::fs;
+use std::io::{ErrorKind, Write};
+use std::path::{Path, PathBuf};
+
+use super::{paths::Paths, snengine_error::SnengineError};
+
+/// Reading and writing the small text files a game keeps between runs.
+pub struct Storage;
+
+impl Storage {
+    /// Returns the contents of the file, or `None` if it doesn't exist yet.
+    pub fn read(path: &Path) -> Result<Option<String>, SnengineError> {
+        match fs::read_to_string(path) {
+            Ok(text) => Ok(Some(text)),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(SnengineError::new(&format!(
+                "Couldn't read {}: {}",
+                path.display(),
+                e
+            ))),
+        }
+    }
+
+    /// Replaces the file with `contents`, creating its directory if needed.
+    ///
+    /// The new contents go to a temporary file next to it which is then
+    /// renamed over the top, so a crash part way through leaves either the
+    /// old file or the new one but never half of each.
+    pub fn write_atomic(path: &Path, contents: &str) -> Result<(), SnengineError> {
+        let error = |e: std::io::Error| {
+            SnengineError::new(&format!("Couldn't write {}: {}", path.display(), e))
+        };
+
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(error)?;
+        }
+
+        let mut tmp = path.as_os_str().to_owned();
+        tmp.push(".tmp");
+        let tmp = Path::new(&tmp);
+
+        let mut file = fs::File::create(tmp).map_err(error)?;
+        file.write_all(contents.as_bytes())
+            .and_then(|_| file.sync_all())
+            .map_err(error)?;
+
+        fs::rename(tmp, path).map_err(error)
+    }
+
+    /// Deletes the file if it's there.
+    pub fn remove(path: &Path) -> Result<(), SnengineError> {
+        match fs::remove_file(path) {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(SnengineError::new(&format!(
+                "Couldn't remove {}: {}",
+                path.display(),
+                e
+            ))),
+        }
+    }
+}
+
+/// One of the game's text files in its data directory, e.g. the high
+/// scores. Each starts with a `version N` line, and the version is bumped
+/// whenever the layout changes in a way older code can't read.
+pub struct DataFile {
+    name: &'static str,
+    // What's kept in it, as told to the player
+    what: &'static str,
+    version: u32,
+}
+
+impl DataFile {
+    pub const fn new(name: &'static str, what: &'static str, version: u32) -> Self {
+        DataFile {
+            name,
+            what,
+            version,
+        }
+    }
+
+    pub fn version(&self) -> u32 {
+        self.version
+    }
+
+    /// Where the file lives, e.g. `~/.local/share/terminal_snake/scores`.
+    pub fn path(&self) -> Option<PathBuf> {
+        Paths::data_dir(env!("CARGO_PKG_NAME")).map(|dir| dir.join(self.name))
+    }
+
+    pub fn exists(&self) -> bool {
+        self.path().is_some_and(|p| p.exists())
+    }
+
+    /// Reads the file and parses it, or `None` if there isn't one yet.
+    /// Anything wrong with it is reported along with the path.
+    pub fn load<T, E: fmt::Display>(
+        &self,
+        parse: impl FnOnce(&str) -> Result<T, E>,
+    ) -> Result<Option<T>, SnengineError> {
+        let path = match self.path() {
+            Some(path) => path,
+            None => return Ok(None),
+        };
+
+        match Storage::read(&path)? {
+            Some(text) => parse(&text)
+                .map(Some)
+                .map_err(|e| SnengineError::new(&format!("{}: {}", path.display(), e))),
+            None => Ok(None),
+        }
+    }
+
+    pub fn save(&self, contents: &str) -> Result<(), SnengineError> {
+        match self.path() {
+            Some(path) => Storage::write_atomic(&path, contents),
+            None => Err(SnengineError::new(&format!(
+                "Nowhere to save {}, set $HOME or $XDG_DATA_HOME",
+                self.what
+            ))),
+        }
+    }
+
+    pub fn remove(&self) -> Result<(), SnengineError> {
+        match self.path() {
+            Some(path) => Storage::remove(&path),
+            None => Ok(()),
+        }
+    }
+
+    /// The lines after the file's `version N` line, trimmed and numbered
+    /// from 1, skipping blank lines and `#` comments. Fails if the version
+    /// isn't one this code can read.
+    pub fn lines<'a>(&self, text: &'a str) -> Result<Vec<(usize, &'a str)>, SnengineError> {
+        let mut lines = text
+            .lines()
+            .enumerate()
+            .map(|(i, line)| (i + 1, line.trim()))
+            .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'));
+
+        if let Some((line_no, line)) = lines.next() {
+            self.check_version(line)
+                .map_err(|e| SnengineError::new(&format!("line {}: {}", line_no, e)))?;
+        }
+
+        Ok(lines.collect())
+    }
+
+    /// Checks the `version N` line the file starts with, saying what's
+    /// wrong if it's not one this code can read.
+    pub fn check_version(&self, line: &str) -> Result<(), String> {
+        let found = line
+            .strip_prefix("version ")
+            .and_then(|v| v.trim().parse::<u32>().ok())
+            .ok_or_else(|| format!("expected `version N`, found '{}'", line))?;
+
+        match found == self.version {
+            true => Ok(()),
+            false => Err(format!(
+                "this file is version {} but only version {} can be loaded",
+                found, self.version
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+    use std::path::PathBuf;
+
+    fn temp_path(name: &str) -> PathBuf {
+        env::temp_dir()
+            .join(format!(
+                "{}-storage-{}",
+                env!("CARGO_PKG_NAME"),
+                std::process::id()
+            ))
+            .join(name)
+    }
+
+    #[test]
+    pub fn read_missing_file_is_none() {
+        let path = temp_path("missing");
+
+        assert_eq!(Storage::read(&path), Ok(None));
+    }
+
+    #[test]
+    pub fn write_atomic_creates_and_replaces() {
+        let path = temp_path("nested/file.txt");
+
+        Storage::write_atomic(&path, "one").unwrap();
+        Storage::write_atomic(&path, "two").unwrap();
+
+        assert_eq!(Storage::read(&path), Ok(Some(String::from("two"))));
+        assert!(!path.with_extension("txt.tmp").exists());
+
+        Storage::remove(&path).unwrap();
+        assert_eq!(Storage::read(&path), Ok(None));
+    }
+
+    #[test]
+    pub fn lines_skip_the_version_and_comments() {
+        let file = DataFile::new("scores", "high scores", 2);
+
+        assert_eq!(
+            file.lines("# scores\nversion 2\n\n  42 sam \n"),
+            Ok(vec![(4, "42 sam")])
+        );
+        assert_eq!(file.lines(""), Ok(vec![]));
+        assert_eq!(
+            file.lines("\nversion 3"),
+            Err(SnengineError::new(
+                "line 2: this file is version 3 but only version 2 can be loaded"
+            ))
+        );
+    }
+
+    #[test]
+    pub fn check_version_wants_the_same_version() {
+        let file = DataFile::new("scores", "high scores", 2);
+
+        assert_eq!(file.check_version("version 2"), Ok(()));
+        assert_eq!(
+            file.check_version("version 1"),
+            Err(String::from(
+                "this file is version 1 but only version 2 can be loaded"
+            ))
+        );
+        assert_eq!(
+            file.check_version("[normal]"),
+            Err(String::from("expected `version N`, found '[normal]'"))
+        );
+    }
+}
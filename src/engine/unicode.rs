@@ -2,6 +2,9 @@
 pub enum Unicode {
     Space,
     HeavyCircleWithCircleInside,
+    BlackStar,
+    BlackCircle,
+    WhiteStar,
     BoxDoubleHorizontal,
     BoxDoubleVertical,
     BoxDoubleDownAndRight,
@@ -14,25 +17,90 @@ pub enum Unicode {
     BoxLightArcDownAndRight,
     BoxLightHorizontal,
     BoxLightVertical,
+    BoxLightVerticalAndRight,
+    BoxLightVerticalAndLeft,
+    BoxLightDownAndHorizontal,
+    BoxLightUpAndHorizontal,
+    BoxLightVerticalAndHorizontal,
+    BlackSquare,
 }
 
 impl Unicode {
+    pub const ALL: [Unicode; 23] = [
+        Unicode::Space,
+        Unicode::HeavyCircleWithCircleInside,
+        Unicode::BlackStar,
+        Unicode::BlackCircle,
+        Unicode::WhiteStar,
+        Unicode::BoxDoubleHorizontal,
+        Unicode::BoxDoubleVertical,
+        Unicode::BoxDoubleDownAndRight,
+        Unicode::BoxDoubleDownAndLeft,
+        Unicode::BoxDoubleUpAndRight,
+        Unicode::BoxDoubleUpAndLeft,
+        Unicode::BoxLightArcDownAndLeft,
+        Unicode::BoxLightArcUpAndLeft,
+        Unicode::BoxLightArcUpAndRight,
+        Unicode::BoxLightArcDownAndRight,
+        Unicode::BoxLightHorizontal,
+        Unicode::BoxLightVertical,
+        Unicode::BoxLightVerticalAndRight,
+        Unicode::BoxLightVerticalAndLeft,
+        Unicode::BoxLightDownAndHorizontal,
+        Unicode::BoxLightUpAndHorizontal,
+        Unicode::BoxLightVerticalAndHorizontal,
+        Unicode::BlackSquare,
+    ];
+
+    /// The variant drawn as `c`, if there is one.
+    pub fn from_char(c: char) -> Option<Unicode> {
+        Unicode::ALL.into_iter().find(|u| u.to_char() == c)
+    }
+
+    /// The light box drawing piece for a wall joined to walls in the given
+    /// directions, with rounded corners. A wall on its own is a square.
+    pub fn joining(up: bool, down: bool, left: bool, right: bool) -> Unicode {
+        match (up, down, left, right) {
+            (false, false, false, false) => Unicode::BlackSquare,
+            (false, false, _, _) => Unicode::BoxLightHorizontal,
+            (_, _, false, false) => Unicode::BoxLightVertical,
+            (false, true, false, true) => Unicode::BoxLightArcDownAndRight,
+            (false, true, true, false) => Unicode::BoxLightArcDownAndLeft,
+            (true, false, false, true) => Unicode::BoxLightArcUpAndRight,
+            (true, false, true, false) => Unicode::BoxLightArcUpAndLeft,
+            (true, true, false, true) => Unicode::BoxLightVerticalAndRight,
+            (true, true, true, false) => Unicode::BoxLightVerticalAndLeft,
+            (false, true, true, true) => Unicode::BoxLightDownAndHorizontal,
+            (true, false, true, true) => Unicode::BoxLightUpAndHorizontal,
+            (true, true, true, true) => Unicode::BoxLightVerticalAndHorizontal,
+        }
+    }
+
     pub fn to_char(&self) -> char {
         match self {
-            Unicode::Space => '\u{0020}',                       // " " (space)
-            Unicode::HeavyCircleWithCircleInside => '\u{2B57}', // ⭗
-            Unicode::BoxDoubleHorizontal => '\u{2550}',         // ═
-            Unicode::BoxDoubleVertical => '\u{2551}',           // ║
-            Unicode::BoxDoubleDownAndRight => '\u{2554}',       // ╔
-            Unicode::BoxDoubleDownAndLeft => '\u{2557}',        // ╗
-            Unicode::BoxDoubleUpAndRight => '\u{255A}',         // ╚
-            Unicode::BoxDoubleUpAndLeft => '\u{255D}',          // ╝
-            Unicode::BoxLightArcDownAndLeft => '\u{256E}',      // ╮
-            Unicode::BoxLightArcUpAndLeft => '\u{256F}',        // ╯
-            Unicode::BoxLightArcUpAndRight => '\u{2570}',       // ╰
-            Unicode::BoxLightArcDownAndRight => '\u{256D}',     // ╭
-            Unicode::BoxLightHorizontal => '\u{2500}',          // ─
-            Unicode::BoxLightVertical => '\u{2502}',            // │
+            Unicode::Space => '\u{0020}',                         // " " (space)
+            Unicode::HeavyCircleWithCircleInside => '\u{2B57}',   // ⭗
+            Unicode::BlackStar => '\u{2605}',                     // ★
+            Unicode::BlackCircle => '\u{25CF}',                   // ●
+            Unicode::WhiteStar => '\u{2606}',                     // ☆
+            Unicode::BoxDoubleHorizontal => '\u{2550}',           // ═
+            Unicode::BoxDoubleVertical => '\u{2551}',             // ║
+            Unicode::BoxDoubleDownAndRight => '\u{2554}',         // ╔
+            Unicode::BoxDoubleDownAndLeft => '\u{2557}',          // ╗
+            Unicode::BoxDoubleUpAndRight => '\u{255A}',           // ╚
+            Unicode::BoxDoubleUpAndLeft => '\u{255D}',            // ╝
+            Unicode::BoxLightArcDownAndLeft => '\u{256E}',        // ╮
+            Unicode::BoxLightArcUpAndLeft => '\u{256F}',          // ╯
+            Unicode::BoxLightArcUpAndRight => '\u{2570}',         // ╰
+            Unicode::BoxLightArcDownAndRight => '\u{256D}',       // ╭
+            Unicode::BoxLightHorizontal => '\u{2500}',            // ─
+            Unicode::BoxLightVertical => '\u{2502}',              // │
+            Unicode::BoxLightVerticalAndRight => '\u{251C}',      // ├
+            Unicode::BoxLightVerticalAndLeft => '\u{2524}',       // ┤
+            Unicode::BoxLightDownAndHorizontal => '\u{252C}',     // ┬
+            Unicode::BoxLightUpAndHorizontal => '\u{2534}',       // ┴
+            Unicode::BoxLightVerticalAndHorizontal => '\u{253C}', // ┼
+            Unicode::BlackSquare => '\u{25A0}',                   // ■
         }
     }
 }
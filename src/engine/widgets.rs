@@ -0,0 +1,18 @@
+pub mod dialog;
+pub mod label;
+pub mod menu;
+pub mod panel;
+pub mod text_input;
+
+/// What a widget did with a key it was given.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WidgetEvent {
+    /// The key means nothing to this widget.
+    Ignored,
+    /// The widget's selection or contents changed.
+    Changed,
+    /// Enter was pressed.
+    Submitted,
+    /// Esc was pressed.
+    Cancelled,
+}
@@ -0,0 +1,215 @@
+use crate::engine::{
+    coords::Rect,
+    graphics::Style,
+    inputhandler::{KeyCode, KeyEvent},
+    renderer::Renderer,
+    snengine_error::SnengineError,
+};
+
+use super::{label::Label, panel::Panel, text_input::TextInput, WidgetEvent};
+
+/// A titled box in the middle of the screen with a message, an optional
+/// text input and a row of buttons.
+///
+/// A scene showing a dialog should hand it every key until it's submitted
+/// or cancelled, and draw it last so it sits on top of everything else.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Dialog {
+    title: String,
+    lines: Vec<String>,
+    input: Option<TextInput>,
+    buttons: Vec<String>,
+    selected: usize,
+}
+
+impl Dialog {
+    /// `message` may span several lines separated by `\n`.
+    pub fn new(title: &str, message: &str, buttons: &[&str]) -> Self {
+        Dialog {
+            title: title.to_string(),
+            lines: message.lines().map(|l| l.to_string()).collect(),
+            input: None,
+            buttons: buttons.iter().map(|b| b.to_string()).collect(),
+            selected: 0,
+        }
+    }
+
+    /// Adds a text input below the message.
+    pub fn with_input(mut self, input: TextInput) -> Self {
+        self.input = Some(input);
+        self
+    }
+
+    /// The index of the highlighted button.
+    pub fn selected(&self) -> usize {
+        self.selected
+    }
+
+    pub fn input(&self) -> Option<&TextInput> {
+        self.input.as_ref()
+    }
+
+    /// Tab moves between buttons, as do Left and Right when there's no text
+    /// input to move around in. Everything else goes to the input.
+    pub fn handle_key(&mut self, key: &KeyEvent) -> WidgetEvent {
+        let count = self.buttons.len().max(1);
+
+        match (key.code, &mut self.input) {
+            (KeyCode::Enter, _) => WidgetEvent::Submitted,
+            (KeyCode::Esc, _) => WidgetEvent::Cancelled,
+            (KeyCode::Tab, _) if count > 1 => {
+                self.selected = match key.modifiers.shift {
+                    true => (self.selected + count - 1) % count,
+                    false => (self.selected + 1) % count,
+                };
+                WidgetEvent::Changed
+            }
+            (KeyCode::Left, None) if self.selected > 0 => {
+                self.selected -= 1;
+                WidgetEvent::Changed
+            }
+            (KeyCode::Right, None) if self.selected + 1 < count => {
+                self.selected += 1;
+                WidgetEvent::Changed
+            }
+            (_, Some(input)) => input.handle_key(key),
+            _ => WidgetEvent::Ignored,
+        }
+    }
+
+    /// The area the dialog covers when drawn on a screen of `cols` x `rows`.
+    pub fn area(&self, cols: u16, rows: u16) -> Rect {
+        let buttons = self.button_row();
+        let widest = self
+            .lines
+            .iter()
+            .map(|l| l.chars().count())
+            .chain([
+                self.title.chars().count() + 2,
+                buttons.chars().count(),
+                self.input.as_ref().map_or(0, |i| i.max_len() + 1),
+            ])
+            .max()
+            .unwrap_or(0) as u16;
+
+        let input_rows = if self.input.is_some() { 2 } else { 0 };
+        let height = self.lines.len() as u16 + input_rows + 2 + 2;
+
+        Rect::centered(Rect::new(1, 1, cols, rows), widest + 4, height)
+    }
+
+    pub fn render(&self, r: &mut dyn Renderer) -> Result<(), SnengineError> {
+        let (cols, rows) = r.size();
+        let area = self.area(cols, rows);
+        let inner = area.inner();
+
+        Panel::titled(&self.title).render(r, area)?;
+
+        // The message and buttons sit one cell in from the border.
+        let content = Rect::new(inner.x + 1, inner.y, inner.width.saturating_sub(2), 1);
+        let mut y = inner.y;
+
+        for line in &self.lines {
+            Label::centered(line).render(r, Rect { y, ..content })?;
+            y += 1;
+        }
+
+        if let Some(input) = &self.input {
+            y += 1;
+
+            let width = (input.max_len() as u16 + 1).min(content.width);
+            let x = content.x + (content.width - width) / 2;
+            input.render(r, Rect::new(x, y, width, 1))?;
+            y += 1;
+        }
+
+        let row = self.button_row();
+        let mut x = content.x + content.width.saturating_sub(row.chars().count() as u16) / 2;
+        y += 1;
+
+        for (i, button) in self.buttons.iter().enumerate() {
+            let text = format!("[ {} ]", button);
+            let style = match i == self.selected {
+                true => Style::REVERSE,
+                false => Style::PLAIN,
+            };
+
+            r.write_styled(x, y, &text, style)?;
+            x += text.chars().count() as u16 + 1;
+        }
+
+        Ok(())
+    }
+
+    fn button_row(&self) -> String {
+        self.buttons
+            .iter()
+            .map(|b| format!("[ {} ]", b))
+            .collect::<Vec<String>>()
+            .join(" ")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{inputhandler::Modifiers, renderer::HeadlessRenderer};
+
+    #[test]
+    pub fn dialog_snapshot() {
+        let dialog = Dialog::new("Quit", "Are you sure?", &["Yes", "No"]);
+        let mut r = HeadlessRenderer::new(24, 7);
+
+        dialog.render(&mut r).unwrap();
+
+        assert_eq!(
+            r.snapshot(),
+            [
+                "",
+                "   ╭───── Quit ─────╮",
+                "   │ Are you sure?  │",
+                "   │                │",
+                "   │ [ Yes ] [ No ] │",
+                "   ╰────────────────╯",
+                "",
+            ]
+            .join("\n")
+        );
+        assert_eq!(r.style_at(6, 5), Some(Style::REVERSE));
+        assert_eq!(r.style_at(14, 5), Some(Style::PLAIN));
+    }
+
+    #[test]
+    pub fn dialog_moves_between_buttons() {
+        let mut dialog = Dialog::new("Quit", "Sure?", &["Yes", "No"]);
+
+        assert_eq!(
+            dialog.handle_key(&KeyEvent::new(KeyCode::Right)),
+            WidgetEvent::Changed
+        );
+        assert_eq!(dialog.selected(), 1);
+        assert_eq!(
+            dialog.handle_key(&KeyEvent::new(KeyCode::Right)),
+            WidgetEvent::Ignored
+        );
+
+        dialog.handle_key(&KeyEvent::with_modifiers(KeyCode::Tab, Modifiers::SHIFT));
+        assert_eq!(dialog.selected(), 0);
+    }
+
+    #[test]
+    pub fn dialog_passes_typing_to_input() {
+        let mut dialog =
+            Dialog::new("High score", "Your name", &["OK"]).with_input(TextInput::new(8));
+
+        for code in [KeyCode::Char('b'), KeyCode::Char('o'), KeyCode::Left] {
+            dialog.handle_key(&KeyEvent::new(code));
+        }
+
+        assert_eq!(dialog.input().unwrap().value(), "bo");
+        assert_eq!(
+            dialog.handle_key(&KeyEvent::new(KeyCode::Enter)),
+            WidgetEvent::Submitted
+        );
+    }
+}
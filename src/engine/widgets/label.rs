@@ -0,0 +1,93 @@
+use crate::engine::{
+    coords::Rect, graphics::Style, renderer::Renderer, snengine_error::SnengineError,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Align {
+    #[default]
+    Left,
+    Center,
+    Right,
+}
+
+/// A line of text.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Label {
+    pub text: String,
+    pub align: Align,
+    pub style: Style,
+}
+
+impl Label {
+    pub fn new(text: &str) -> Self {
+        Label {
+            text: text.to_string(),
+            align: Align::Left,
+            style: Style::PLAIN,
+        }
+    }
+
+    pub fn centered(text: &str) -> Self {
+        Label {
+            align: Align::Center,
+            ..Label::new(text)
+        }
+    }
+
+    pub fn with_style(mut self, style: Style) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// Draws the label on the top row of `area`, cut short if it's too long.
+    pub fn render(&self, r: &mut dyn Renderer, area: Rect) -> Result<(), SnengineError> {
+        if area.width == 0 || area.height == 0 {
+            return Ok(());
+        }
+
+        let text: String = self.text.chars().take(area.width as usize).collect();
+        let spare = area.width - text.chars().count() as u16;
+
+        let x = match self.align {
+            Align::Left => area.x,
+            Align::Center => area.x + spare / 2,
+            Align::Right => area.x + spare,
+        };
+
+        r.write_styled(x, area.y, &text, self.style)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::renderer::HeadlessRenderer;
+
+    #[test]
+    pub fn label_aligns_within_area() {
+        let mut r = HeadlessRenderer::new(10, 3);
+        let area = |y| Rect::new(2, y, 8, 1);
+
+        Label::new("ab").render(&mut r, area(1)).unwrap();
+        Label::centered("ab").render(&mut r, area(2)).unwrap();
+        Label {
+            align: Align::Right,
+            ..Label::new("ab")
+        }
+        .render(&mut r, area(3))
+        .unwrap();
+
+        assert_eq!(r.snapshot(), " ab\n    ab\n       ab");
+    }
+
+    #[test]
+    pub fn label_is_cut_to_fit() {
+        let mut r = HeadlessRenderer::new(10, 1);
+
+        Label::centered("abcdef")
+            .render(&mut r, Rect::new(1, 1, 4, 1))
+            .unwrap();
+
+        assert_eq!(r.row(1).trim_end(), "abcd");
+    }
+}
@@ -0,0 +1,175 @@
+use crate::engine::{
+    coords::Rect,
+    graphics::Style,
+    inputhandler::{KeyCode, KeyEvent},
+    renderer::Renderer,
+    snengine_error::SnengineError,
+};
+
+use super::WidgetEvent;
+
+/// A vertical list of items, one of which is selected and highlighted.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Menu {
+    items: Vec<String>,
+    selected: usize,
+}
+
+impl Menu {
+    pub fn new(items: &[&str]) -> Self {
+        Menu {
+            items: items.iter().map(|i| i.to_string()).collect(),
+            selected: 0,
+        }
+    }
+
+    pub fn items(&self) -> &[String] {
+        &self.items
+    }
+
+    pub fn selected(&self) -> usize {
+        self.selected
+    }
+
+    /// Selects item `i`, or the last item if there aren't that many.
+    pub fn select(&mut self, i: usize) {
+        self.selected = i.min(self.items.len().saturating_sub(1));
+    }
+
+    /// The width needed to show every item with a space either side.
+    pub fn width(&self) -> u16 {
+        self.items
+            .iter()
+            .map(|i| i.chars().count() as u16 + 2)
+            .max()
+            .unwrap_or(0)
+    }
+
+    pub fn height(&self) -> u16 {
+        self.items.len() as u16
+    }
+
+    /// Up and Down move the selection, Home and End jump to either end.
+    pub fn handle_key(&mut self, key: &KeyEvent) -> WidgetEvent {
+        let previous = self.selected;
+
+        match key.code {
+            KeyCode::Up => self.select(self.selected.saturating_sub(1)),
+            KeyCode::Down => self.select(self.selected + 1),
+            KeyCode::Home | KeyCode::PageUp => self.select(0),
+            KeyCode::End | KeyCode::PageDown => self.select(self.items.len()),
+            KeyCode::Enter => return WidgetEvent::Submitted,
+            KeyCode::Esc => return WidgetEvent::Cancelled,
+            _ => return WidgetEvent::Ignored,
+        }
+
+        match self.selected == previous {
+            true => WidgetEvent::Ignored,
+            false => WidgetEvent::Changed,
+        }
+    }
+
+    /// Draws one item per row of `area`, each centered. If there are more
+    /// items than rows, the list scrolls to keep the selection in view.
+    pub fn render(&self, r: &mut dyn Renderer, area: Rect) -> Result<(), SnengineError> {
+        if area.width == 0 || area.height == 0 {
+            return Ok(());
+        }
+
+        let scroll = (self.selected + 1).saturating_sub(area.height as usize);
+
+        for (row, (i, item)) in self
+            .items
+            .iter()
+            .enumerate()
+            .skip(scroll)
+            .take(area.height as usize)
+            .enumerate()
+        {
+            let item: String = item.chars().take(area.width as usize).collect();
+            let spare = area.width as usize - item.chars().count();
+            let line = format!(
+                "{}{}{}",
+                " ".repeat(spare / 2),
+                item,
+                " ".repeat(spare - spare / 2)
+            );
+
+            let style = match i == self.selected {
+                true => Style::REVERSE,
+                false => Style::PLAIN,
+            };
+
+            r.write_styled(area.x, area.y + row as u16, &line, style)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::renderer::HeadlessRenderer;
+
+    fn key(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code)
+    }
+
+    #[test]
+    pub fn menu_moves_within_bounds() {
+        let mut menu = Menu::new(&["Start", "Options", "Exit"]);
+
+        assert_eq!(menu.handle_key(&key(KeyCode::Up)), WidgetEvent::Ignored);
+        assert_eq!(menu.handle_key(&key(KeyCode::Down)), WidgetEvent::Changed);
+        assert_eq!(menu.selected(), 1);
+
+        menu.handle_key(&key(KeyCode::End));
+        assert_eq!(menu.selected(), 2);
+        assert_eq!(menu.handle_key(&key(KeyCode::Down)), WidgetEvent::Ignored);
+
+        menu.handle_key(&key(KeyCode::Home));
+        assert_eq!(menu.selected(), 0);
+    }
+
+    #[test]
+    pub fn menu_submits_and_cancels() {
+        let mut menu = Menu::new(&["a"]);
+
+        assert_eq!(
+            menu.handle_key(&key(KeyCode::Enter)),
+            WidgetEvent::Submitted
+        );
+        assert_eq!(menu.handle_key(&key(KeyCode::Esc)), WidgetEvent::Cancelled);
+        assert_eq!(
+            menu.handle_key(&key(KeyCode::Char('x'))),
+            WidgetEvent::Ignored
+        );
+    }
+
+    #[test]
+    pub fn menu_highlights_selection() {
+        let mut menu = Menu::new(&["Start", "Exit"]);
+        menu.select(1);
+        let mut r = HeadlessRenderer::new(9, 2);
+
+        menu.render(&mut r, Rect::new(1, 1, menu.width(), 2))
+            .unwrap();
+
+        assert_eq!(r.snapshot(), " Start\n Exit");
+        assert_eq!(r.style_at(1, 1), Some(Style::PLAIN));
+        assert_eq!(r.style_at(1, 2), Some(Style::REVERSE));
+        assert_eq!(r.style_at(7, 2), Some(Style::REVERSE));
+    }
+
+    #[test]
+    pub fn menu_scrolls_to_selection() {
+        let mut menu = Menu::new(&["a", "b", "c", "d"]);
+        menu.select(3);
+        let mut r = HeadlessRenderer::new(3, 2);
+
+        menu.render(&mut r, Rect::new(1, 1, 3, 2)).unwrap();
+
+        assert_eq!(r.snapshot(), " c\n d");
+    }
+}
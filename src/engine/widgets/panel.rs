@@ -0,0 +1,102 @@
+use crate::engine::{
+    coords::Rect, graphics::Style, renderer::Renderer, snengine_error::SnengineError,
+    unicode::Unicode,
+};
+
+/// A box with rounded corners and an optional title in the top border.
+/// Everything inside the border is blanked, so a panel can be drawn over
+/// the top of whatever else is on screen.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Panel {
+    pub title: Option<String>,
+    pub style: Style,
+}
+
+impl Panel {
+    pub fn new() -> Self {
+        Panel::default()
+    }
+
+    pub fn titled(title: &str) -> Self {
+        Panel {
+            title: Some(title.to_string()),
+            ..Panel::default()
+        }
+    }
+
+    pub fn render(&self, r: &mut dyn Renderer, area: Rect) -> Result<(), SnengineError> {
+        if area.width < 2 || area.height < 2 {
+            return Ok(());
+        }
+
+        let inner = area.inner();
+        let horizontal = Unicode::BoxLightHorizontal
+            .to_char()
+            .to_string()
+            .repeat(inner.width as usize);
+        let blank = " ".repeat(inner.width as usize);
+
+        let top = format!(
+            "{}{}{}",
+            Unicode::BoxLightArcDownAndRight.to_char(),
+            horizontal,
+            Unicode::BoxLightArcDownAndLeft.to_char()
+        );
+        let bottom = format!(
+            "{}{}{}",
+            Unicode::BoxLightArcUpAndRight.to_char(),
+            horizontal,
+            Unicode::BoxLightArcUpAndLeft.to_char()
+        );
+        let side = Unicode::BoxLightVertical.to_char().to_string();
+
+        r.write_styled(area.x, area.y, &top, self.style)?;
+
+        for y in inner.y..area.bottom() {
+            r.write_styled(area.x, y, &side, self.style)?;
+            r.write(inner.x, y, &blank)?;
+            r.write_styled(area.right(), y, &side, self.style)?;
+        }
+
+        r.write_styled(area.x, area.bottom(), &bottom, self.style)?;
+
+        if let Some(title) = &self.title {
+            let title: String = format!(" {} ", title)
+                .chars()
+                .take(inner.width as usize)
+                .collect();
+            let x = inner.x + (inner.width - title.chars().count() as u16) / 2;
+
+            r.write_styled(x, area.y, &title, self.style)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::renderer::HeadlessRenderer;
+
+    #[test]
+    pub fn panel_snapshot() {
+        let mut r = HeadlessRenderer::new(10, 4);
+        r.write(1, 2, "xxxxxxxxxx").unwrap();
+
+        Panel::titled("Hi")
+            .render(&mut r, Rect::new(2, 1, 8, 4))
+            .unwrap();
+
+        assert_eq!(r.snapshot(), " ╭─ Hi ─╮\nx│      │x\n │      │\n ╰──────╯");
+    }
+
+    #[test]
+    pub fn panel_too_small_draws_nothing() {
+        let mut r = HeadlessRenderer::new(4, 4);
+
+        Panel::new().render(&mut r, Rect::new(1, 1, 1, 4)).unwrap();
+
+        assert_eq!(r.snapshot(), "\n\n\n");
+    }
+}
@@ -0,0 +1,178 @@
+use crate::engine::{
+    coords::Rect,
+    graphics::Style,
+    inputhandler::{KeyCode, KeyEvent},
+    renderer::Renderer,
+    snengine_error::SnengineError,
+};
+
+use super::WidgetEvent;
+
+/// A single line of editable text with a cursor.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextInput {
+    value: Vec<char>,
+    cursor: usize,
+    max_len: usize,
+}
+
+impl TextInput {
+    /// An empty input which takes at most `max_len` characters.
+    pub fn new(max_len: usize) -> Self {
+        TextInput {
+            value: Vec::new(),
+            cursor: 0,
+            max_len,
+        }
+    }
+
+    /// Starts with `value` already typed in, cursor at the end.
+    pub fn with_value(mut self, value: &str) -> Self {
+        self.value = value.chars().take(self.max_len).collect();
+        self.cursor = self.value.len();
+        self
+    }
+
+    pub fn value(&self) -> String {
+        self.value.iter().collect()
+    }
+
+    pub fn max_len(&self) -> usize {
+        self.max_len
+    }
+
+    pub fn handle_key(&mut self, key: &KeyEvent) -> WidgetEvent {
+        let typing = !key.modifiers.ctrl && !key.modifiers.alt;
+
+        match key.code {
+            KeyCode::Char(c) if typing && !c.is_control() => {
+                if self.value.len() >= self.max_len {
+                    return WidgetEvent::Ignored;
+                }
+
+                self.value.insert(self.cursor, c);
+                self.cursor += 1;
+            }
+            KeyCode::Backspace if self.cursor > 0 => {
+                self.cursor -= 1;
+                self.value.remove(self.cursor);
+            }
+            KeyCode::Delete if self.cursor < self.value.len() => {
+                self.value.remove(self.cursor);
+            }
+            KeyCode::Left if self.cursor > 0 => self.cursor -= 1,
+            KeyCode::Right if self.cursor < self.value.len() => self.cursor += 1,
+            KeyCode::Home => self.cursor = 0,
+            KeyCode::End => self.cursor = self.value.len(),
+            KeyCode::Enter => return WidgetEvent::Submitted,
+            KeyCode::Esc => return WidgetEvent::Cancelled,
+            _ => return WidgetEvent::Ignored,
+        }
+
+        WidgetEvent::Changed
+    }
+
+    /// Draws the text on the top row of `area` with the empty space after
+    /// it underscored. The text scrolls if the cursor would be off the end.
+    pub fn render(&self, r: &mut dyn Renderer, area: Rect) -> Result<(), SnengineError> {
+        if area.width == 0 || area.height == 0 {
+            return Ok(());
+        }
+
+        let width = area.width as usize;
+        let scroll = (self.cursor + 1).saturating_sub(width);
+
+        let line: String = self
+            .value
+            .iter()
+            .chain(std::iter::repeat(&'_'))
+            .skip(scroll)
+            .take(width)
+            .collect();
+
+        r.write(area.x, area.y, &line)?;
+
+        let under_cursor = self.value.get(self.cursor).copied().unwrap_or(' ');
+        r.write_styled(
+            area.x + (self.cursor - scroll) as u16,
+            area.y,
+            &under_cursor.to_string(),
+            Style::REVERSE,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{inputhandler::Modifiers, renderer::HeadlessRenderer};
+
+    fn type_keys(input: &mut TextInput, codes: &[KeyCode]) {
+        for code in codes {
+            input.handle_key(&KeyEvent::new(*code));
+        }
+    }
+
+    #[test]
+    pub fn text_input_edits_at_cursor() {
+        let mut input = TextInput::new(10);
+
+        type_keys(
+            &mut input,
+            &[
+                KeyCode::Char('a'),
+                KeyCode::Char('c'),
+                KeyCode::Left,
+                KeyCode::Char('b'),
+                KeyCode::End,
+                KeyCode::Backspace,
+                KeyCode::Home,
+                KeyCode::Delete,
+            ],
+        );
+
+        assert_eq!(input.value(), "b");
+    }
+
+    #[test]
+    pub fn text_input_stops_at_max_len() {
+        let mut input = TextInput::new(2).with_value("abc");
+        assert_eq!(input.value(), "ab");
+
+        let event = input.handle_key(&KeyEvent::new(KeyCode::Char('d')));
+
+        assert_eq!(event, WidgetEvent::Ignored);
+        assert_eq!(input.value(), "ab");
+    }
+
+    #[test]
+    pub fn text_input_ignores_control_keys() {
+        let mut input = TextInput::new(5);
+
+        let event = input.handle_key(&KeyEvent::with_modifiers(
+            KeyCode::Char('a'),
+            Modifiers::CTRL,
+        ));
+
+        assert_eq!(event, WidgetEvent::Ignored);
+        assert_eq!(
+            input.handle_key(&KeyEvent::new(KeyCode::Enter)),
+            WidgetEvent::Submitted
+        );
+    }
+
+    #[test]
+    pub fn text_input_renders_cursor_and_scrolls() {
+        let mut r = HeadlessRenderer::new(4, 2);
+
+        let input = TextInput::new(10).with_value("ab");
+        input.render(&mut r, Rect::new(1, 1, 4, 1)).unwrap();
+
+        let input = TextInput::new(10).with_value("abcdef");
+        input.render(&mut r, Rect::new(1, 2, 4, 1)).unwrap();
+
+        assert_eq!(r.snapshot(), "ab _\ndef");
+        assert_eq!(r.style_at(3, 1), Some(Style::REVERSE));
+        assert_eq!(r.style_at(4, 2), Some(Style::REVERSE));
+    }
+}
@@ -1,17 +1,48 @@
 use std::{error::Error, fmt};
 
-pub struct SnakeError;
+use crate::engine::snengine_error::SnengineError;
+
+#[derive(PartialEq)]
+pub struct SnakeError {
+    message: String,
+}
 
 impl Error for SnakeError {}
 
+impl SnakeError {
+    pub fn new(message: &str) -> Self {
+        SnakeError {
+            message: message.to_string(),
+        }
+    }
+}
+
+impl Default for SnakeError {
+    fn default() -> Self {
+        SnakeError::new("Something broke.")
+    }
+}
+
+impl From<SnengineError> for SnakeError {
+    fn from(e: SnengineError) -> Self {
+        SnakeError::new(&e.to_string())
+    }
+}
+
 impl fmt::Display for SnakeError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "Something broke.")
+        write!(f, "{}", self.message)
     }
 }
 
 impl fmt::Debug for SnakeError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{{ file: {}, line: {} }}", file!(), line!())
+        write!(
+            f,
+            "{{ file: {}, line: {}, message: {} }}",
+            file!(),
+            line!(),
+            self.message
+        )
     }
 }
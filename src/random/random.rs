@@ -1,47 +1,274 @@
 //
-// Linear congruential generator (LCG) aka Snake Food.
+// Random numbers for the game, aka Snake Food.
+//
+// There are two generators. PCG32 is what the game uses. The original linear
+// congruential generator (LCG) is kept so `Random::new` still gives exactly
+// the numbers it always has.
 //
 
+use std::fmt;
+use std::str::FromStr;
 use std::time::{SystemTime, UNIX_EPOCH};
 
-#[derive(Debug)]
+use crate::error::SnakeError;
+
+// PCG32's multiplier, and the stream used when none is given.
+const PCG_MULTIPLIER: u64 = 6364136223846793005;
+const PCG_DEFAULT_STREAM: u64 = 1442695040888963407;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Generator {
+    Lcg { state: u32 },
+    Pcg32 { state: u64, inc: u64 },
+}
+
+#[derive(Debug, Clone, PartialEq)]
 pub struct Random {
-    seed: u32,
+    generator: Generator,
 }
 
 impl Random {
-    fn new(seed: u32) -> Self {
-        Random { seed }
+    //
+    // The legacy LCG, seeded with `seed`.
+    //
+    pub fn new(seed: u32) -> Self {
+        Random {
+            generator: Generator::Lcg { state: seed },
+        }
     }
 
     //
-    // Gets a new instance of `Random` with a seed of nanoseconds since `UNIX_EPOCH`.
+    // PCG32 on its default stream. The same seed always gives the same numbers.
     //
-    pub fn time_seed() -> Self {
-        match SystemTime::now().duration_since(UNIX_EPOCH) {
-            Ok(n) => Random::new((n.subsec_nanos() / 10) ^ 9),
-            Err(_) => panic!("Your clock is broken, or something."),
+    pub fn seeded(seed: u64) -> Self {
+        Random::pcg(seed, PCG_DEFAULT_STREAM)
+    }
+
+    //
+    // PCG32 on one of its 2^63 streams, seeded the way the reference
+    // implementation's `pcg32_srandom` does it.
+    //
+    pub fn pcg(seed: u64, stream: u64) -> Self {
+        let mut r = Random {
+            generator: Generator::Pcg32 {
+                state: 0,
+                inc: (stream << 1) | 1,
+            },
+        };
+
+        r.next();
+
+        if let Generator::Pcg32 { state, .. } = &mut r.generator {
+            *state = state.wrapping_add(seed);
         }
+
+        r.next();
+        r
+    }
+
+    //
+    // Gets a new PCG32 `Random` seeded from the whole time since `UNIX_EPOCH`
+    // in nanoseconds, mixed with the process id so two games started in the
+    // same instant still differ.
+    //
+    pub fn time_seed() -> Self {
+        // A clock set before 1970 just means a less surprising seed.
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos() as u64)
+            .unwrap_or_default();
+
+        Random::seeded(nanos ^ ((std::process::id() as u64) << 32))
     }
 
     //
-    // The LCG algorithm
-    // https://en.wikipedia.org/wiki/Linear_congruential_generator
+    // The next number from the generator, anywhere in the range of a u32.
     //
+    pub fn next_u32(&mut self) -> u32 {
+        self.next()
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        ((self.next() as u64) << 32) | self.next() as u64
+    }
+
+    //
+    // A float from 0 up to but not including 1.
+    //
+    pub fn next_f64(&mut self) -> f64 {
+        // 53 random bits fill an f64's mantissa exactly.
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    //
+    // True with the given probability, from 0 (never) to 1 (always).
+    //
+    pub fn chance(&mut self, probability: f64) -> bool {
+        self.next_f64() < probability
+    }
+
     fn next(&mut self) -> u32 {
-        let a: u32 = 1664525;
-        let c = 1013904223;
-        let m = u32::MAX;
+        match &mut self.generator {
+            //
+            // The LCG algorithm
+            // https://en.wikipedia.org/wiki/Linear_congruential_generator
+            //
+            // The `% m` is left over from an attempt at mod 2^32, which the
+            // wrapping arithmetic already gives. It's kept so old sequences
+            // don't change.
+            //
+            Generator::Lcg { state } => {
+                let a: u32 = 1664525;
+                let c = 1013904223;
+                let m = u32::MAX;
 
-        self.seed = a.wrapping_mul(self.seed).wrapping_add(c) % m;
-        self.seed
+                *state = a.wrapping_mul(*state).wrapping_add(c) % m;
+                *state
+            }
+            //
+            // PCG32 (XSH RR)
+            // https://www.pcg-random.org/
+            //
+            Generator::Pcg32 { state, inc } => {
+                let old = *state;
+                *state = old.wrapping_mul(PCG_MULTIPLIER).wrapping_add(*inc);
+
+                let xorshifted = (((old >> 18) ^ old) >> 27) as u32;
+                let rot = (old >> 59) as u32;
+
+                xorshifted.rotate_right(rot)
+            }
+        }
     }
 
     //
-    // Some modulo wizardry to effectively get an output from the LCG within a min/max range
+    // A number from min to max inclusive, which may be given either way round.
+    // Numbers from the short end of the generator's range are thrown away so
+    // every result is equally likely.
     //
     pub fn get(&mut self, min: u32, max: u32) -> u32 {
-        min + (self.next() % (max - min + 1))
+        let (min, max) = if max < min { (max, min) } else { (min, max) };
+        let range = (max - min).wrapping_add(1);
+
+        // min and max cover every u32.
+        if range == 0 {
+            return self.next();
+        }
+
+        // 2^32 % range, the count of numbers which would make the low end
+        // of the range come up more often.
+        let threshold = range.wrapping_neg() % range;
+
+        loop {
+            let n = self.next();
+
+            if n >= threshold {
+                return min + n % range;
+            }
+        }
+    }
+
+    //
+    // An index from 0 up to but not including `len`, which must not be 0.
+    //
+    pub fn below(&mut self, len: usize) -> usize {
+        debug_assert!(len > 0, "can't pick from nothing");
+
+        match u32::try_from(len - 1) {
+            Ok(max) => self.get(0, max) as usize,
+            Err(_) => {
+                // The same as `get`, with 64 bit numbers.
+                let len = len as u64;
+                let threshold = len.wrapping_neg() % len;
+
+                loop {
+                    let n = self.next_u64();
+
+                    if n >= threshold {
+                        return (n % len) as usize;
+                    }
+                }
+            }
+        }
+    }
+
+    //
+    // A random item from the slice, or None if it's empty.
+    //
+    pub fn choose<'a, T>(&mut self, items: &'a [T]) -> Option<&'a T> {
+        match items.is_empty() {
+            true => None,
+            false => Some(&items[self.below(items.len())]),
+        }
+    }
+
+    //
+    // Picks an item with a chance in proportion to its weight. Items with a
+    // weight of 0 are never picked. None if there's nothing to pick.
+    //
+    pub fn weighted<'a, T>(&mut self, items: &'a [(T, u32)]) -> Option<&'a T> {
+        let total: usize = items.iter().map(|(_, w)| *w as usize).sum();
+
+        if total == 0 {
+            return None;
+        }
+
+        let mut pick = self.below(total);
+
+        for (item, weight) in items {
+            if pick < *weight as usize {
+                return Some(item);
+            }
+
+            pick -= *weight as usize;
+        }
+
+        None
+    }
+
+    //
+    // Puts the items into a random order (Fisher-Yates).
+    //
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            let j = self.below(i + 1);
+            items.swap(i, j);
+        }
+    }
+}
+
+//
+// Writes the generator and its state, e.g. `pcg32 <state> <inc>` or
+// `lcg <state>`, so a game can be saved and carry on with the same numbers.
+//
+impl fmt::Display for Random {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.generator {
+            Generator::Lcg { state } => write!(f, "lcg {}", state),
+            Generator::Pcg32 { state, inc } => write!(f, "pcg32 {} {}", state, inc),
+        }
+    }
+}
+
+impl FromStr for Random {
+    type Err = SnakeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parts: Vec<&str> = s.split_whitespace().collect();
+
+        let generator = match parts.as_slice() {
+            ["lcg", state] => state.parse().ok().map(|state| Generator::Lcg { state }),
+            ["pcg32", state, inc] => match (state.parse(), inc.parse::<u64>()) {
+                // The increment is always odd.
+                (Ok(state), Ok(inc)) if inc % 2 == 1 => Some(Generator::Pcg32 { state, inc }),
+                _ => None,
+            },
+            _ => None,
+        };
+
+        generator
+            .map(|generator| Random { generator })
+            .ok_or_else(|| SnakeError::new(&format!("'{}' is not a random number state", s)))
     }
 }
 
@@ -92,4 +319,111 @@ mod tests {
             assert!(n >= 1 && n <= 100);
         }
     }
+
+    #[test]
+    fn pcg_matches_reference_output() {
+        // From the reference implementation's pcg32-demo, seeded 42, 54.
+        let mut r = Random::pcg(42, 54);
+
+        for expected in [0xa15c02b7, 0x7b47f409, 0xba1d3330, 0x83d2f293, 0xbfa4784b] {
+            assert_eq!(r.next_u32(), expected);
+        }
+    }
+
+    #[test]
+    fn get_accepts_bounds_either_way_round() {
+        let mut r = Random::seeded(1);
+
+        for _ in 0..1000 {
+            assert!((5..=9).contains(&r.get(9, 5)));
+        }
+
+        assert_eq!(r.get(3, 3), 3);
+        r.get(0, u32::MAX);
+    }
+
+    #[test]
+    fn get_is_unbiased() {
+        let mut r = Random::seeded(7);
+        let mut counts = [0; 6];
+
+        for _ in 0..60000 {
+            counts[r.get(0, 5) as usize] += 1;
+        }
+
+        // Each should be close to 10000.
+        assert!(counts.iter().all(|c| (9500..10500).contains(c)));
+    }
+
+    #[test]
+    fn next_f64_is_between_0_and_1() {
+        let mut r = Random::seeded(3);
+
+        for _ in 0..10000 {
+            assert!((0.0..1.0).contains(&r.next_f64()));
+        }
+    }
+
+    #[test]
+    fn choose_and_weighted_pick_from_the_items() {
+        let mut r = Random::seeded(5);
+        let empty: [u8; 0] = [];
+
+        assert_eq!(r.choose(&empty), None);
+        assert!([1, 2, 3].contains(r.choose(&[1, 2, 3]).unwrap()));
+
+        assert_eq!(r.weighted::<u8>(&[]), None);
+        assert_eq!(r.weighted(&[("never", 0), ("always", 3)]), Some(&"always"));
+    }
+
+    #[test]
+    fn weighted_follows_the_weights() {
+        let mut r = Random::seeded(9);
+        let mut heavy = 0;
+
+        for _ in 0..10000 {
+            if r.weighted(&[(true, 3), (false, 1)]) == Some(&true) {
+                heavy += 1;
+            }
+        }
+
+        assert!((7200..7800).contains(&heavy));
+    }
+
+    #[test]
+    fn weighted_picks_like_below() {
+        let mut a = Random::seeded(5);
+        let mut b = Random::seeded(5);
+        let items = [(0, 1), (1, 1), (2, 1)];
+
+        for _ in 0..100 {
+            assert_eq!(a.weighted(&items), Some(&b.below(3)));
+        }
+    }
+
+    #[test]
+    fn shuffle_keeps_every_item() {
+        let mut r = Random::seeded(11);
+        let mut items: Vec<u32> = (0..50).collect();
+
+        r.shuffle(&mut items);
+        assert_ne!(items, (0..50).collect::<Vec<u32>>());
+
+        items.sort();
+        assert_eq!(items, (0..50).collect::<Vec<u32>>());
+    }
+
+    #[test]
+    fn state_round_trips_through_text() {
+        for mut r in [Random::new(42), Random::seeded(42)] {
+            r.next();
+
+            let mut restored: Random = r.to_string().parse().unwrap();
+            assert_eq!(restored, r);
+            assert_eq!(restored.next(), r.next());
+        }
+
+        assert!("pcg32 1 2".parse::<Random>().is_err());
+        assert!("xorshift 1".parse::<Random>().is_err());
+    }
 }
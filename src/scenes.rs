@@ -0,0 +1,8 @@
+pub mod campaign;
+pub mod context;
+pub mod game_over;
+pub mod high_scores;
+pub mod level_complete;
+pub mod options;
+pub mod playing;
+pub mod title;
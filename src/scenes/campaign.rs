@@ -0,0 +1,83 @@
+use std::error::Error;
+
+use crate::{
+    engine::{
+        inputhandler::KeyEvent,
+        renderer::Renderer,
+        scene::{Scene, Transition},
+        widgets::{menu::Menu, WidgetEvent},
+    },
+    error::SnakeError,
+    state::{campaign::Campaign, level::Level},
+};
+
+use super::{context::Context, playing::Playing, title::draw_menu_screen, title::Title};
+
+/// Lists the campaign's levels, with those not reached yet locked. Picking
+/// an unlocked one starts it from a score of 0.
+pub struct CampaignScene {
+    levels: Vec<Level>,
+    menu: Menu,
+}
+
+impl CampaignScene {
+    pub fn new(ctx: &Context) -> Result<Self, SnakeError> {
+        let levels = Campaign::levels()?;
+
+        let items: Vec<String> = levels
+            .iter()
+            .enumerate()
+            .map(|(i, level)| match ctx.progress.is_unlocked(i) {
+                true => format!("{}. {}", i + 1, level.name),
+                false => format!("{}. Locked", i + 1),
+            })
+            .collect();
+        let items: Vec<&str> = items.iter().map(|i| i.as_str()).collect();
+
+        // Start on the furthest level reached.
+        let mut menu = Menu::new(&items);
+        menu.select(
+            (0..levels.len())
+                .rev()
+                .find(|&i| ctx.progress.is_unlocked(i))
+                .unwrap_or(0),
+        );
+
+        Ok(CampaignScene { levels, menu })
+    }
+}
+
+impl Scene<Context> for CampaignScene {
+    fn handle_key(
+        &mut self,
+        ctx: &mut Context,
+        key: KeyEvent,
+    ) -> Result<Transition<Context>, Box<dyn Error>> {
+        Ok(match self.menu.handle_key(&ctx.menu_key(key)) {
+            WidgetEvent::Submitted if ctx.progress.is_unlocked(self.menu.selected()) => {
+                Transition::Switch(Box::new(Playing::campaign(ctx, self.menu.selected(), 0)?))
+            }
+            WidgetEvent::Cancelled => Transition::Switch(Box::new(Title::new())),
+            _ => Transition::None,
+        })
+    }
+
+    fn render(&mut self, ctx: &Context, r: &mut dyn Renderer) -> Result<(), Box<dyn Error>> {
+        let selected = self.menu.selected();
+        let level = &self.levels[selected];
+
+        let goal = match (ctx.progress.is_unlocked(selected), level.goal) {
+            (false, _) => String::from("Complete the level before to unlock"),
+            (true, Some(goal)) => goal.describe(),
+            (true, None) => String::from("Play as long as you can"),
+        };
+        let best = match ctx.progress.best(&level.name) {
+            Some(points) => format!("Best: {} points", points),
+            None => String::from("Not completed yet"),
+        };
+
+        draw_menu_screen(r, "Campaign", &[&goal, &best], &self.menu)?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,60 @@
+use crate::{
+    cli::Options,
+    engine::inputhandler::{KeyCode, KeyEvent},
+    state::{
+        controls::{Action, Controls},
+        directions::Directions,
+        highscores::HighScores,
+        level::Level,
+        progress::Progress,
+    },
+};
+
+/// Everything shared between scenes for the life of the program.
+pub struct Context {
+    pub controls: Controls,
+    pub options: Options,
+    pub high_scores: HighScores,
+    // How far through the campaign the player has got.
+    pub progress: Progress,
+    // Played instead of the classic arena, from --level.
+    pub level: Option<Level>,
+    // The name last put against a high score, offered again next time.
+    pub player_name: String,
+}
+
+impl Context {
+    pub fn new(
+        controls: Controls,
+        options: Options,
+        high_scores: HighScores,
+        progress: Progress,
+        level: Option<Level>,
+    ) -> Self {
+        Context {
+            controls,
+            options,
+            high_scores,
+            progress,
+            level,
+            player_name: std::env::var("USER").unwrap_or_default(),
+        }
+    }
+
+    //
+    // Menus answer to the arrows, Enter and Esc, plus whatever the player has
+    // bound to turning and quitting, so WASD and hjkl work too.
+    // Those keys are turned into the arrow or Esc they stand for here.
+    //
+    pub fn menu_key(&self, key: KeyEvent) -> KeyEvent {
+        match self.controls.action_for(&key) {
+            Some(Action::Turn(Directions::Up)) => KeyEvent::new(KeyCode::Up),
+            Some(Action::Turn(Directions::Down)) => KeyEvent::new(KeyCode::Down),
+            Some(Action::Turn(Directions::Left)) => KeyEvent::new(KeyCode::Left),
+            Some(Action::Turn(Directions::Right)) => KeyEvent::new(KeyCode::Right),
+            Some(Action::Quit) => KeyEvent::new(KeyCode::Esc),
+            _ if key.code == KeyCode::Char(' ') => KeyEvent::new(KeyCode::Enter),
+            _ => key,
+        }
+    }
+}
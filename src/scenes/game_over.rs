@@ -0,0 +1,153 @@
+use std::error::Error;
+
+use crate::{
+    engine::{
+        inputhandler::KeyEvent,
+        renderer::Renderer,
+        scene::{Scene, Transition},
+        widgets::{dialog::Dialog, menu::Menu, text_input::TextInput, WidgetEvent},
+    },
+    error::SnakeError,
+    state::{
+        gamestate::GameState,
+        highscores::{Board, MAX_NAME_LEN},
+    },
+};
+
+use super::{
+    context::Context,
+    high_scores::HighScoresScene,
+    playing::Playing,
+    title::{draw_menu_screen, Title},
+};
+
+// A dialog waiting for an answer, which takes every key until it gets one.
+enum Prompt {
+    Name(Dialog),
+    Error(Dialog),
+}
+
+/// Shown when a round ends, with the score and a choice of what next. A
+/// score good enough for the high score table asks for a name first.
+pub struct GameOver {
+    score: i16,
+    quit: bool,
+    won: bool,
+    // The level's goal was reached.
+    complete: bool,
+    // The campaign level to retry and the score it started with.
+    campaign: Option<(usize, i16)>,
+    board: Board,
+    rank: Option<usize>,
+    menu: Menu,
+    prompt: Option<Prompt>,
+}
+
+impl GameOver {
+    pub fn new(ctx: &Context, state: &GameState) -> Result<Self, SnakeError> {
+        let board = Board::for_game(state)?;
+
+        let prompt = match ctx.high_scores.qualifies(&board, state.score) {
+            true => Some(Prompt::Name(
+                Dialog::new("New high score!", "Enter your name", &["OK"])
+                    .with_input(TextInput::new(MAX_NAME_LEN).with_value(&ctx.player_name)),
+            )),
+            false => None,
+        };
+
+        Ok(GameOver {
+            score: state.score,
+            quit: state.quit,
+            won: state.won,
+            complete: state.won && state.goal.is_some_and(|g| g.reached(state)),
+            campaign: state.campaign.map(|index| (index, state.start_score)),
+            board,
+            rank: None,
+            menu: Menu::new(&["Retry", "High scores", "Menu"]),
+            prompt,
+        })
+    }
+
+    //
+    // Puts the score on the board and writes the table out. Failing to save
+    // isn't fatal, the player is told and the score is kept for this run.
+    //
+    fn record(&mut self, ctx: &mut Context, name: String) -> Option<Prompt> {
+        self.rank = ctx.high_scores.insert(self.board, &name, self.score);
+        ctx.player_name = name;
+
+        match ctx.high_scores.save() {
+            Ok(()) => None,
+            Err(e) => Some(Prompt::Error(Dialog::new(
+                "Couldn't save high scores",
+                &e.to_string(),
+                &["OK"],
+            ))),
+        }
+    }
+}
+
+impl Scene<Context> for GameOver {
+    fn handle_key(
+        &mut self,
+        ctx: &mut Context,
+        key: KeyEvent,
+    ) -> Result<Transition<Context>, Box<dyn Error>> {
+        if let Some(prompt) = self.prompt.take() {
+            self.prompt = match prompt {
+                Prompt::Name(mut dialog) => match dialog.handle_key(&key) {
+                    WidgetEvent::Submitted => {
+                        let name = dialog.input().map(|i| i.value()).unwrap_or_default();
+                        self.record(ctx, name)
+                    }
+                    WidgetEvent::Cancelled => None,
+                    _ => Some(Prompt::Name(dialog)),
+                },
+                Prompt::Error(mut dialog) => match dialog.handle_key(&key) {
+                    WidgetEvent::Submitted | WidgetEvent::Cancelled => None,
+                    _ => Some(Prompt::Error(dialog)),
+                },
+            };
+
+            return Ok(Transition::None);
+        }
+
+        Ok(match self.menu.handle_key(&ctx.menu_key(key)) {
+            WidgetEvent::Submitted => match self.menu.selected() {
+                0 => match self.campaign {
+                    Some((index, score)) => {
+                        Transition::Switch(Box::new(Playing::campaign(ctx, index, score)?))
+                    }
+                    None => Transition::Switch(Box::new(Playing::new(ctx)?)),
+                },
+                1 => Transition::Push(Box::new(HighScoresScene::new(
+                    ctx,
+                    Some(self.board),
+                    self.rank,
+                ))),
+                _ => Transition::Switch(Box::new(Title::new())),
+            },
+            WidgetEvent::Cancelled => Transition::Switch(Box::new(Title::new())),
+            _ => Transition::None,
+        })
+    }
+
+    fn render(&mut self, _ctx: &Context, r: &mut dyn Renderer) -> Result<(), Box<dyn Error>> {
+        let heading = match (self.complete, self.won, self.quit) {
+            (true, _, _) => "You win! Level complete",
+            (_, true, _) => "You win! There's no room left",
+            (_, _, true) => "Bye!",
+            _ => "x_x you died",
+        };
+        let score = format!("You got {}", self.score);
+
+        draw_menu_screen(r, "Game over", &[heading, &score], &self.menu)?;
+
+        match &self.prompt {
+            Some(Prompt::Name(dialog)) | Some(Prompt::Error(dialog)) => dialog.render(r)?,
+            None => {}
+        }
+
+        Ok(())
+    }
+}
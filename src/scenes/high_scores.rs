@@ -0,0 +1,110 @@
+use std::error::Error;
+
+use crate::{
+    engine::{
+        coords::Rect,
+        graphics::Style,
+        inputhandler::{KeyCode, KeyEvent},
+        renderer::Renderer,
+        scene::{Scene, Transition},
+        widgets::{label::Label, panel::Panel},
+    },
+    state::highscores::{Board, MAX_ENTRIES, MAX_NAME_LEN},
+};
+
+use super::context::Context;
+
+/// The best scores, one board at a time. Left and Right flip between
+/// boards, Enter or Esc goes back.
+pub struct HighScoresScene {
+    boards: Vec<Board>,
+    shown: usize,
+    // The entry to pick out, as (board, rank), e.g. a score just made.
+    highlight: Option<(Board, usize)>,
+}
+
+impl HighScoresScene {
+    /// Opens on `board` if it has any scores, otherwise on the first board.
+    pub fn new(ctx: &Context, board: Option<Board>, rank: Option<usize>) -> Self {
+        let boards = ctx.high_scores.boards();
+        let shown = board
+            .and_then(|b| boards.iter().position(|o| *o == b))
+            .unwrap_or(0);
+
+        HighScoresScene {
+            boards,
+            shown,
+            highlight: board.zip(rank),
+        }
+    }
+}
+
+impl Scene<Context> for HighScoresScene {
+    fn handle_key(
+        &mut self,
+        ctx: &mut Context,
+        key: KeyEvent,
+    ) -> Result<Transition<Context>, Box<dyn Error>> {
+        let count = self.boards.len().max(1);
+
+        match ctx.menu_key(key).code {
+            KeyCode::Left => self.shown = (self.shown + count - 1) % count,
+            KeyCode::Right => self.shown = (self.shown + 1) % count,
+            KeyCode::Enter | KeyCode::Esc => return Ok(Transition::Pop),
+            _ => {}
+        }
+
+        Ok(Transition::None)
+    }
+
+    fn render(&mut self, ctx: &Context, r: &mut dyn Renderer) -> Result<(), Box<dyn Error>> {
+        let (cols, rows) = r.size();
+
+        // Rank, name and score columns.
+        let width = 4 + MAX_NAME_LEN as u16 + 6;
+        let area = Rect::centered(
+            Rect::new(1, 1, cols, rows),
+            width + 4,
+            MAX_ENTRIES as u16 + 4,
+        );
+        let inner = area.inner();
+        let line = |i: u16| Rect::new(inner.x + 1, inner.y + i, width, 1);
+
+        Panel::titled("High scores").render(r, area)?;
+
+        let board = match self.boards.get(self.shown) {
+            Some(board) => *board,
+            None => {
+                Label::centered("No scores yet").render(r, line(0))?;
+                return Ok(());
+            }
+        };
+
+        let heading = match self.boards.len() {
+            1 => board.name(),
+            _ => format!("< {} >", board.name()),
+        };
+        Label::centered(&heading).render(r, line(0))?;
+
+        for (i, entry) in ctx.high_scores.entries(&board).iter().enumerate() {
+            let style = match self.highlight == Some((board, i)) {
+                true => Style::REVERSE,
+                false => Style::PLAIN,
+            };
+
+            let text = format!(
+                "{:>2}. {:<name$} {:>5}",
+                i + 1,
+                entry.name,
+                entry.score,
+                name = MAX_NAME_LEN
+            );
+
+            Label::new(&text)
+                .with_style(style)
+                .render(r, line(i as u16 + 2))?;
+        }
+
+        Ok(())
+    }
+}
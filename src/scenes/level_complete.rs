@@ -0,0 +1,109 @@
+use std::error::Error;
+
+use crate::{
+    engine::{
+        inputhandler::KeyEvent,
+        renderer::Renderer,
+        scene::{Scene, Transition},
+        widgets::{dialog::Dialog, menu::Menu, WidgetEvent},
+    },
+    state::{campaign::Campaign, gamestate::GameState},
+};
+
+use super::{context::Context, game_over::GameOver, playing::Playing, title::draw_menu_screen};
+
+/// Shown between campaign levels. The level is marked complete, unlocking
+/// the next, and the player goes on or stops with the score so far.
+pub struct LevelComplete {
+    state: GameState,
+    index: usize,
+    points: i16,
+    new_best: bool,
+    menu: Menu,
+    // Shown when the progress couldn't be saved.
+    error: Option<Dialog>,
+}
+
+impl LevelComplete {
+    pub fn new(ctx: &mut Context, state: GameState) -> Self {
+        let index = state.campaign.unwrap_or(0);
+        let points = state.score - state.start_score;
+        let name = state.level.clone().unwrap_or_default();
+
+        let new_best = ctx.progress.complete(index, &name, points);
+
+        // Failing to save isn't fatal, the level stays unlocked for this run.
+        let error = ctx
+            .progress
+            .save()
+            .err()
+            .map(|e| Dialog::new("Couldn't save campaign progress", &e.to_string(), &["OK"]));
+
+        let items: &[&str] = match Campaign::is_last(index) {
+            true => &["Finish"],
+            false => &["Next level", "Stop here"],
+        };
+
+        LevelComplete {
+            state,
+            index,
+            points,
+            new_best,
+            menu: Menu::new(items),
+            error,
+        }
+    }
+}
+
+impl Scene<Context> for LevelComplete {
+    fn handle_key(
+        &mut self,
+        ctx: &mut Context,
+        key: KeyEvent,
+    ) -> Result<Transition<Context>, Box<dyn Error>> {
+        if let Some(mut dialog) = self.error.take() {
+            if dialog.handle_key(&key) == WidgetEvent::Ignored {
+                self.error = Some(dialog);
+            }
+
+            return Ok(Transition::None);
+        }
+
+        match self.menu.handle_key(&ctx.menu_key(key)) {
+            WidgetEvent::Submitted
+                if self.menu.selected() == 0 && !Campaign::is_last(self.index) =>
+            {
+                Ok(Transition::Switch(Box::new(Playing::campaign(
+                    ctx,
+                    self.index + 1,
+                    self.state.score,
+                )?)))
+            }
+            WidgetEvent::Submitted | WidgetEvent::Cancelled => Ok(Transition::Switch(Box::new(
+                GameOver::new(ctx, &self.state)?,
+            ))),
+            _ => Ok(Transition::None),
+        }
+    }
+
+    fn render(&mut self, _ctx: &Context, r: &mut dyn Renderer) -> Result<(), Box<dyn Error>> {
+        let title = match Campaign::is_last(self.index) {
+            true => "Campaign complete",
+            false => "Level complete",
+        };
+        let name = self.state.level.clone().unwrap_or_default();
+        let points = match self.new_best {
+            true => format!("{} points, a new best!", self.points),
+            false => format!("{} points", self.points),
+        };
+        let total = format!("Score {}", self.state.score);
+
+        draw_menu_screen(r, title, &[&name, &points, &total], &self.menu)?;
+
+        if let Some(dialog) = &self.error {
+            dialog.render(r)?;
+        }
+
+        Ok(())
+    }
+}
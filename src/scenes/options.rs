@@ -0,0 +1,59 @@
+use std::error::Error;
+
+use crate::{
+    engine::{
+        inputhandler::KeyEvent,
+        renderer::Renderer,
+        scene::{Scene, Transition},
+        widgets::{menu::Menu, WidgetEvent},
+    },
+    state::difficulty::Difficulty,
+};
+
+use super::{context::Context, title::draw_menu_screen};
+
+/// Settings reachable from the title menu. Picking a difficulty goes back.
+pub struct OptionsScene {
+    menu: Menu,
+}
+
+impl OptionsScene {
+    pub fn new(ctx: &Context) -> Self {
+        let names: Vec<&str> = Difficulty::ALL.iter().map(|d| d.name()).collect();
+        let mut menu = Menu::new(&names);
+
+        menu.select(
+            Difficulty::ALL
+                .iter()
+                .position(|d| *d == ctx.options.difficulty)
+                .unwrap_or(0),
+        );
+
+        OptionsScene { menu }
+    }
+}
+
+impl Scene<Context> for OptionsScene {
+    fn handle_key(
+        &mut self,
+        ctx: &mut Context,
+        key: KeyEvent,
+    ) -> Result<Transition<Context>, Box<dyn Error>> {
+        Ok(match self.menu.handle_key(&ctx.menu_key(key)) {
+            WidgetEvent::Submitted => {
+                ctx.options.difficulty = Difficulty::ALL[self.menu.selected()];
+                Transition::Pop
+            }
+            WidgetEvent::Cancelled => Transition::Pop,
+            _ => Transition::None,
+        })
+    }
+
+    fn render(&mut self, ctx: &Context, r: &mut dyn Renderer) -> Result<(), Box<dyn Error>> {
+        let current = format!("Current: {}", ctx.options.difficulty.name());
+
+        draw_menu_screen(r, "Difficulty", &[&current], &self.menu)?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,668 @@
+use std::error::Error;
+use std::time::Duration;
+
+use crate::{
+    engine::{
+        clock::{GameClock, RealTime},
+        coords::{Coords, Rect},
+        graphics::Style,
+        inputhandler::KeyEvent,
+        renderer::Renderer,
+        scene::{Scene, Transition},
+        snengine_error::SnengineError,
+        unicode::Unicode,
+        widgets::{dialog::Dialog, label::Label, panel::Panel, WidgetEvent},
+    },
+    error::SnakeError,
+    random::random::Random,
+    state::{
+        arena::Arena,
+        campaign::Campaign,
+        controls::{Action, Controls},
+        directions::Directions,
+        food::Food,
+        gamestate::GameState,
+        level::Level,
+        mode::Mode,
+        save::SaveGame,
+    },
+};
+
+use crate::terminal::terminal::Terminal;
+
+use super::{context::Context, game_over::GameOver, level_complete::LevelComplete, title::Title};
+
+// How often the screen is redrawn, about 120 fps.
+const RENDER_INTERVAL: Duration = Duration::from_millis(8);
+
+/// A round of the game, from the first step until the snake dies, fills
+/// the arena or the player quits.
+pub struct Playing {
+    state: GameState,
+    clock: GameClock<RealTime>,
+    fits: bool,
+    paused: bool,
+    boost: bool,
+    pause_hint: String,
+    render_due: bool,
+    // Shown over the paused game when saving it failed.
+    error: Option<Dialog>,
+}
+
+impl Playing {
+    pub fn new(ctx: &Context) -> Result<Self, Box<dyn Error>> {
+        let (state, fits) = Playing::start(ctx, ctx.level.as_ref(), |_| {})?;
+
+        Ok(Playing::with_state(ctx, state, fits))
+    }
+
+    /// Starts a level of the campaign, carrying the score so far into it
+    /// unless the level starts afresh.
+    pub fn campaign(ctx: &Context, index: usize, score: i16) -> Result<Self, Box<dyn Error>> {
+        let level = Campaign::level(index)?;
+
+        let (state, fits) = Playing::start(ctx, Some(&level), |state| {
+            state.mode = Mode::Campaign;
+            state.campaign = Some(index);
+
+            if level.carry {
+                state.score = score;
+                state.start_score = score;
+            }
+        })?;
+
+        Ok(Playing::with_state(ctx, state, fits))
+    }
+
+    //
+    // Builds a new game on the given level, or classic's arena, and lays it
+    // out in the window. `setup` gets the state before anything is placed.
+    //
+    fn start(
+        ctx: &Context,
+        level: Option<&Level>,
+        setup: impl FnOnce(&mut GameState),
+    ) -> Result<(GameState, bool), Box<dyn Error>> {
+        let mut state = GameState::new();
+        state.difficulty = ctx.options.difficulty;
+
+        if let Some(seed) = ctx.options.seed {
+            state.rng = Random::seeded(seed);
+        }
+
+        // Save the console size to GameState for easy access.
+        let (c_x, c_y) = Terminal::get_console_size();
+        state.c_dimensions = Coords::new(c_x as i16, c_y as i16);
+
+        state = match level {
+            Some(level) => level.apply(state),
+            None => Arena::create_level_1(state),
+        };
+        setup(&mut state);
+
+        let fits = layout(&mut state)?;
+        state.place_snake()?;
+        let _ = Food::fill(&mut state)?;
+
+        // Snake sets off straight away on levels that say which way.
+        if let Some(level) = level {
+            state.snake.direction = level.direction;
+        }
+
+        Ok((state, fits))
+    }
+
+    /// Picks up a saved game, paused so the player can get ready. Fails if
+    /// the arena it was saved with doesn't fit in the window.
+    pub fn resume(ctx: &Context, mut state: GameState) -> Result<Self, SnakeError> {
+        let (c_x, c_y) = Terminal::get_console_size();
+        state.c_dimensions = Coords::new(c_x as i16, c_y as i16);
+
+        if !layout(&mut state)? {
+            let (width, height) = Arena::size(&state.arena)?;
+
+            return Err(SnakeError::new(&format!(
+                "This game was saved with a {}x{} arena, which doesn't fit in a {}x{} window. Make the window bigger and try again.",
+                width, height, c_x, c_y
+            )));
+        }
+
+        let mut playing = Playing::with_state(ctx, state, true);
+        playing.paused = true;
+
+        Ok(playing)
+    }
+
+    fn with_state(ctx: &Context, state: GameState, fits: bool) -> Self {
+        let clock = GameClock::new(RealTime::new(), state.tick_interval(), RENDER_INTERVAL);
+
+        let pause_hint = format!(
+            "{} resume  {} quit  {} save",
+            key_name(&ctx.controls, Action::Pause),
+            key_name(&ctx.controls, Action::Quit),
+            key_name(&ctx.controls, Action::SaveQuit)
+        );
+
+        Playing {
+            state,
+            clock,
+            fits,
+            paused: false,
+            boost: false,
+            pause_hint,
+            render_due: true,
+            error: None,
+        }
+    }
+}
+
+impl Scene<Context> for Playing {
+    fn handle_key(
+        &mut self,
+        ctx: &mut Context,
+        key: KeyEvent,
+    ) -> Result<Transition<Context>, Box<dyn Error>> {
+        if let Some(mut dialog) = self.error.take() {
+            if dialog.handle_key(&key) == WidgetEvent::Ignored {
+                self.error = Some(dialog);
+            }
+
+            self.render_due = true;
+            return Ok(Transition::None);
+        }
+
+        // Turns are queued up rather than lost when several keys arrive
+        // between ticks. Nothing moves while paused or the window is too
+        // small, so steering is ignored then rather than saved up.
+        let steering = !self.paused && self.fits;
+
+        match ctx.controls.action_for(&key) {
+            Some(Action::Turn(direction)) if steering => {
+                Controls::turn(&mut self.state.snake, direction);
+            }
+            Some(Action::Boost) if steering => self.boost = true,
+            Some(Action::Pause) => {
+                self.paused = !self.paused;
+                self.render_due = true;
+            }
+            Some(Action::Quit) => self.state.quit = true,
+            Some(Action::SaveQuit) => match SaveGame::save(&self.state) {
+                Ok(()) => return Ok(Transition::Switch(Box::new(Title::new()))),
+                Err(e) => {
+                    self.paused = true;
+                    self.render_due = true;
+                    self.error = Some(Dialog::new(
+                        "Couldn't save the game",
+                        &e.to_string(),
+                        &["OK"],
+                    ));
+                }
+            },
+            _ => {}
+        }
+
+        Ok(Transition::None)
+    }
+
+    fn update(&mut self, ctx: &mut Context) -> Result<Transition<Context>, Box<dyn Error>> {
+        // The game is also paused until the window is big enough again.
+        if self.paused || !self.fits {
+            self.clock.pause();
+        } else {
+            self.clock.resume();
+        }
+
+        let frame = self.clock.update();
+
+        for _ in 0..frame.ticks {
+            // Turns are already queued up on snake as they're pressed.
+            let action = self.boost.then_some(Action::Boost);
+            self.boost = false;
+
+            let _ = self.state.tick(action)?;
+
+            // Speed up as the score goes up.
+            self.clock.set_tick_interval(self.state.tick_interval());
+        }
+
+        // Campaign levels go on to the next one when they're won.
+        if self.state.won && self.state.campaign.is_some() {
+            let state = std::mem::take(&mut self.state);
+            return Ok(Transition::Switch(Box::new(LevelComplete::new(ctx, state))));
+        }
+
+        if self.state.snake.x_x || self.state.quit || self.state.won {
+            return Ok(Transition::Switch(Box::new(GameOver::new(
+                ctx,
+                &self.state,
+            )?)));
+        }
+
+        self.render_due |= frame.ticks > 0;
+
+        Ok(Transition::None)
+    }
+
+    fn render(&mut self, _ctx: &Context, r: &mut dyn Renderer) -> Result<(), Box<dyn Error>> {
+        self.render_due = false;
+
+        if !self.fits {
+            draw_too_small(&self.state, r)?;
+            return Ok(());
+        }
+
+        draw_arena(&self.state, r)?;
+        draw_snake_body(&self.state, r)?;
+        draw_food(&self.state, r)?;
+        draw_score(&self.state, r)?;
+        draw_bonus(&self.state, r)?;
+        draw_diags(&self.state, r)?;
+
+        if self.paused {
+            draw_paused(&self.state, &self.pause_hint, r)?;
+        }
+
+        if let Some(dialog) = &self.error {
+            dialog.render(r)?;
+        }
+
+        Ok(())
+    }
+
+    fn resize(&mut self, _ctx: &mut Context, cols: u16, rows: u16) -> Result<(), Box<dyn Error>> {
+        self.state.c_dimensions = Coords::new(cols as i16, rows as i16);
+        self.fits = layout(&mut self.state)?;
+        self.render_due = true;
+
+        Ok(())
+    }
+
+    fn wake_after(&self) -> Option<Duration> {
+        // Nothing moves while paused, so just wait for a key.
+        match self.paused || !self.fits {
+            true => None,
+            false => Some(self.clock.until_next()),
+        }
+    }
+
+    fn needs_render(&self) -> bool {
+        self.render_due
+    }
+}
+
+fn draw_snake_body(state: &GameState, r: &mut dyn Renderer) -> Result<(), SnengineError> {
+    for (i, p) in state.snake.positions.iter().enumerate() {
+        let (x, y) = p.coords.to_unsigned_tuple();
+
+        if x == 0 || y == 0 {
+            continue;
+        }
+
+        let previous_block_facing = match i {
+            1.. => &state.snake.positions[i - 1].facing,
+            _ => &p.facing,
+        };
+
+        // Draw the current block depending on the previous facing
+        // vs. the current facing.  Draw corner pieces etc. accordingly.
+        match (previous_block_facing, &p.facing) {
+            (Directions::Down, Directions::Left) | (Directions::Right, Directions::Up) => {
+                r.draw_char(x, y, Unicode::BoxDoubleDownAndRight)?
+            }
+            (Directions::Up, Directions::Left) | (Directions::Right, Directions::Down) => {
+                r.draw_char(x, y, Unicode::BoxDoubleUpAndRight)?
+            }
+            (Directions::Down, Directions::Right) | (Directions::Left, Directions::Up) => {
+                r.draw_char(x, y, Unicode::BoxDoubleDownAndLeft)?
+            }
+            (Directions::Left, Directions::Down) | (Directions::Up, Directions::Right) => {
+                r.draw_char(x, y, Unicode::BoxDoubleUpAndLeft)?
+            }
+            (Directions::Left, Directions::Left) | (Directions::Right, Directions::Right) => {
+                r.draw_char(x, y, Unicode::BoxDoubleHorizontal)?
+            }
+            _ => {
+                // Down, Up and None
+                r.draw_char(x, y, Unicode::BoxDoubleVertical)?
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn draw_arena(state: &GameState, r: &mut dyn Renderer) -> Result<(), SnengineError> {
+    // todo: add DrawingError?
+    for (coords, char) in &state.arena.positions {
+        let (x, y) = coords.to_unsigned_tuple();
+        r.draw_char(x, y, char.clone())?;
+    }
+
+    Ok(())
+}
+
+//
+// The score goes top right, with the level's name and how close the goal
+// is in the middle.
+//
+fn draw_score(state: &GameState, r: &mut dyn Renderer) -> Result<(), SnengineError> {
+    let (cols, _) = state.c_dimensions.to_unsigned_tuple();
+
+    let heading = match (&state.level, state.goal) {
+        (Some(name), Some(goal)) => format!("{}  {}", name, goal.progress(state)),
+        (Some(name), None) => name.clone(),
+        (None, Some(goal)) => goal.progress(state),
+        (None, None) => String::new(),
+    };
+
+    Label::centered(&heading).render(r, Rect::new(1, 2, cols, 1))?;
+
+    let score = state.score.to_string();
+
+    r.write(cols + 1 - score.chars().count().max(2) as u16, 2, &score)
+}
+
+//
+// Shows how long the bonus has left, top left above the arena.
+//
+fn draw_bonus(state: &GameState, r: &mut dyn Renderer) -> Result<(), SnengineError> {
+    let bonus = match state.food.bonus() {
+        Some(b) => b,
+        None => return Ok(()),
+    };
+
+    let text = format!(
+        "{} {}",
+        bonus.glyph().to_char(),
+        bonus.ticks_left.unwrap_or(0)
+    );
+
+    r.write_styled(1, 2, &text, Style::fg(bonus.kind.color()))
+}
+
+fn draw_food(state: &GameState, r: &mut dyn Renderer) -> Result<(), SnengineError> {
+    for f in &state.food.items {
+        let (x, y) = f.coords.to_unsigned_tuple();
+        let glyph = f.glyph().to_char().to_string();
+
+        r.write_styled(x, y, &glyph, Style::fg(f.kind.color()))?
+    }
+
+    Ok(())
+}
+
+fn draw_diags(state: &GameState, r: &mut dyn Renderer) -> Result<(), SnengineError> {
+    // We'll draw the diagnostic output on the bottom row.
+    let (c_x, c_y) = state.c_dimensions.to_unsigned_tuple();
+
+    for i in 1..c_x {
+        r.draw_char(i, c_y, Unicode::Space)?;
+    }
+
+    // Displays `Facing: Up`
+    r.write(
+        1,
+        c_y,
+        format!("Current facing: {:?}", state.snake.direction).as_str(),
+    )?;
+
+    // Displays `Speed: 10.0/s (normal)`
+    r.write(
+        26,
+        c_y,
+        format!(
+            "Speed: {:.1}/s ({})",
+            1.0 / state.tick_interval().as_secs_f64(),
+            state.difficulty.name()
+        )
+        .as_str(),
+    )?;
+
+    // Displays head coordinates
+    // Graphics::write(
+    //     30,
+    //     c_y,
+    //     format!("Head coords: {:?}", state.snake.positions[0]).as_str()
+    // )?;
+
+    for i in 1..c_x {
+        r.draw_char(i, c_y - 1, Unicode::BoxLightHorizontal)?;
+    }
+
+    Ok(())
+}
+
+//
+// Centers the fixed size arena horizontally in the console, below the score.
+// Returns false if the console is too small to fit the arena and the HUD.
+//
+fn layout(state: &mut GameState) -> Result<bool, SnakeError> {
+    let (cols, rows) = state.c_dimensions.to_unsigned_tuple();
+    let (width, _) = Arena::size(&state.arena)?;
+    let (min_x, _) = Arena::min_arena_coords(&state.arena)?;
+    let (_, max_y) = Arena::max_arena_coords(&state.arena)?;
+
+    // The diagnostics bar takes up the bottom two rows.
+    if width > cols || max_y + 2 > rows {
+        return Ok(false);
+    }
+
+    let x = (cols - width) / 2 + 1;
+    state.translate(x as i16 - min_x as i16, 0);
+
+    Ok(true)
+}
+
+//
+// Draws a box saying the game is paused in the middle of the arena.
+//
+fn draw_paused(state: &GameState, hint: &str, r: &mut dyn Renderer) -> Result<(), Box<dyn Error>> {
+    let (min_x, min_y) = Arena::min_arena_coords(&state.arena)?;
+    let (max_x, max_y) = Arena::max_arena_coords(&state.arena)?;
+
+    let lines = ["Paused", hint];
+    let inner = lines.iter().map(|l| l.chars().count()).max().unwrap_or(0) as u16 + 2;
+
+    let arena = Rect::new(min_x, min_y, max_x + 1 - min_x, max_y + 1 - min_y);
+    let area = Rect::centered(arena, inner + 2, lines.len() as u16 + 2);
+
+    Panel::new().render(r, area)?;
+
+    for (i, line) in lines.iter().enumerate() {
+        let row = area.inner();
+        Label::centered(line).render(
+            r,
+            Rect {
+                y: row.y + i as u16,
+                ..row
+            },
+        )?;
+    }
+
+    Ok(())
+}
+
+//
+// The name of a key bound to the action, for showing in hints.
+//
+fn key_name(controls: &Controls, action: Action) -> String {
+    match controls.key_for(action) {
+        Some(key) => key.to_string(),
+        None => String::from("?"),
+    }
+}
+
+fn draw_too_small(state: &GameState, r: &mut dyn Renderer) -> Result<(), SnengineError> {
+    let (cols, rows) = state.c_dimensions.to_unsigned_tuple();
+
+    let lines = ["Window too small", "Make it bigger to continue"];
+
+    for (i, line) in lines.iter().enumerate() {
+        let y = (rows / 2).max(1) + i as u16;
+
+        Label::centered(line).render(r, Rect::new(1, y, cols, 1))?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        cli::Options,
+        engine::{graphics::Color, inputhandler::KeyCode, renderer::HeadlessRenderer},
+        state::{
+            food::{FoodItem, FoodKind},
+            goal::Goal,
+            highscores::HighScores,
+            progress::Progress,
+        },
+    };
+
+    use super::*;
+
+    //
+    // Snake turning from Right to Down with a single piece of food ahead of
+    // him.
+    //
+    fn set_state() -> GameState {
+        let mut state = GameState::turning_snake();
+        state.food.items = vec![FoodItem::new(Coords::new(7, 5), FoodKind::Apple)];
+        state.sync_board();
+
+        state
+    }
+
+    #[test]
+    fn draw_frame_snapshot() {
+        let state = set_state();
+        let mut r = HeadlessRenderer::new(20, 14);
+
+        draw_arena(&state, &mut r).unwrap();
+        draw_snake_body(&state, &mut r).unwrap();
+        draw_food(&state, &mut r).unwrap();
+
+        let mut expected = vec!["", "", "╭───────╮", "│  ═╗   │", "│   ║ ⭗ │", "╰───────╯"];
+        expected.resize(14, "");
+
+        assert_eq!(r.snapshot(), expected.join("\n"));
+        assert_eq!(r.style_at(7, 5), Some(Style::fg(Color::Red)));
+    }
+
+    #[test]
+    fn draw_hud_snapshot() {
+        let mut state = set_state();
+        state.score = 42;
+        let mut r = HeadlessRenderer::new(20, 14);
+
+        draw_score(&state, &mut r).unwrap();
+        draw_diags(&state, &mut r).unwrap();
+
+        assert_eq!(r.row(2), format!("{}42", " ".repeat(18)));
+        assert_eq!(r.row(13), "─".repeat(19) + " ");
+        assert_eq!(r.row(14), "Current facing: Down");
+    }
+
+    #[test]
+    fn draw_hud_shows_the_goal() {
+        let mut state = set_state();
+        state.level = Some(String::from("Pillars"));
+        state.goal = Some(Goal::Length(12));
+        state.score = 7;
+        state.c_dimensions = Coords::new(30, 14);
+        let mut r = HeadlessRenderer::new(30, 14);
+
+        draw_score(&state, &mut r).unwrap();
+
+        assert_eq!(r.row(2), "      Pillars  3/12 long    7 ");
+    }
+
+    #[test]
+    fn draw_bonus_countdown() {
+        let mut state = set_state();
+        let mut bonus = FoodItem::new(Coords::new(3, 5), FoodKind::Golden);
+        bonus.ticks_left = Some(23);
+        state.food.items.push(bonus);
+        let mut r = HeadlessRenderer::new(20, 14);
+
+        draw_bonus(&state, &mut r).unwrap();
+        draw_food(&state, &mut r).unwrap();
+
+        assert_eq!(r.row(2).trim_end(), "★ 23");
+        assert_eq!(r.style_at(1, 2), Some(Style::fg(Color::Yellow)));
+        assert_eq!(r.char_at(3, 5), Some('★'));
+
+        state.food.items[1].ticks_left = Some(1);
+        let _ = Food::tick(&mut state).unwrap();
+        let mut r = HeadlessRenderer::new(20, 14);
+
+        draw_bonus(&state, &mut r).unwrap();
+        draw_food(&state, &mut r).unwrap();
+
+        assert_eq!(r.row(2).trim_end(), "");
+        assert_eq!(r.char_at(3, 5), Some(' '));
+    }
+
+    #[test]
+    fn draw_snake_after_a_step() {
+        let mut state = set_state();
+        state.snake.direction = Directions::Left;
+        let mut r = HeadlessRenderer::new(20, 14);
+
+        let _ = state.tick(None).unwrap();
+        draw_snake_body(&state, &mut r).unwrap();
+
+        assert_eq!(state.snake.positions[0].coords, Coords::new(4, 5));
+        assert!(!state.snake.x_x);
+        assert_eq!(r.char_at(4, 4), Some(' '));
+        assert_eq!(r.char_at(5, 4), Some('╗'));
+        assert_eq!(r.char_at(5, 5), Some('╝'));
+        assert_eq!(r.char_at(4, 5), Some('═'));
+    }
+
+    #[test]
+    fn steering_is_ignored_while_the_window_is_too_small() {
+        let mut ctx = Context::new(
+            Controls::default(),
+            Options::default(),
+            HighScores::new(),
+            Progress::new(),
+            None,
+        );
+        let mut playing = Playing::with_state(&ctx, set_state(), false);
+
+        for code in [KeyCode::Left, KeyCode::Char(' ')] {
+            playing.handle_key(&mut ctx, KeyEvent::new(code)).unwrap();
+        }
+
+        assert!(playing.state.snake.pending_turns.is_empty());
+        assert!(!playing.boost);
+
+        playing.fits = true;
+        playing
+            .handle_key(&mut ctx, KeyEvent::new(KeyCode::Left))
+            .unwrap();
+
+        assert_eq!(playing.state.snake.next_direction(), Directions::Left);
+    }
+
+    #[test]
+    fn draw_paused_snapshot() {
+        let mut state = set_state();
+        state.c_dimensions = Coords::new(30, 20);
+        state.arena = Arena::new();
+        state = Arena::create_level_1(state);
+        let mut r = HeadlessRenderer::new(30, 20);
+
+        draw_arena(&state, &mut r).unwrap();
+        draw_snake_body(&state, &mut r).unwrap();
+        draw_food(&state, &mut r).unwrap();
+        draw_paused(&state, "p resume", &mut r).unwrap();
+
+        assert_eq!(r.row(3).trim_end(), "╭────────────╮");
+        assert_eq!(r.row(4).trim_end(), "│╭──────────╮│");
+        assert_eq!(r.row(5).trim_end(), "││  Paused  ││");
+        assert_eq!(r.row(6).trim_end(), "││ p resume ││");
+        assert_eq!(r.row(7).trim_end(), "│╰──────────╯│");
+    }
+}
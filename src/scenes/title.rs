@@ -0,0 +1,182 @@
+use std::error::Error;
+
+use crate::{
+    engine::{
+        coords::Rect,
+        inputhandler::KeyEvent,
+        renderer::Renderer,
+        scene::{Scene, Transition},
+        snengine_error::SnengineError,
+        widgets::{dialog::Dialog, label::Label, menu::Menu, panel::Panel, WidgetEvent},
+    },
+    error::SnakeError,
+    state::save::SaveGame,
+};
+
+use super::{
+    campaign::CampaignScene, context::Context, high_scores::HighScoresScene, options::OptionsScene,
+    playing::Playing,
+};
+
+// What each of the title menu's items does.
+#[derive(Clone, Copy)]
+enum Choice {
+    Continue,
+    Start,
+    Campaign,
+    HighScores,
+    Options,
+    Exit,
+}
+
+impl Choice {
+    fn label(self) -> &'static str {
+        match self {
+            Choice::Continue => "Continue",
+            Choice::Start => "Start",
+            Choice::Campaign => "Campaign",
+            Choice::HighScores => "High scores",
+            Choice::Options => "Options",
+            Choice::Exit => "Exit",
+        }
+    }
+}
+
+/// The first thing the player sees: Start, Campaign, High scores, Options
+/// and Exit, plus Continue when there's a saved game.
+pub struct Title {
+    // One for each of the menu's items, in the same order.
+    choices: Vec<Choice>,
+    menu: Menu,
+    error: Option<Dialog>,
+}
+
+impl Title {
+    pub fn new() -> Self {
+        let mut choices = vec![
+            Choice::Start,
+            Choice::Campaign,
+            Choice::HighScores,
+            Choice::Options,
+            Choice::Exit,
+        ];
+
+        if SaveGame::exists() {
+            choices.insert(0, Choice::Continue);
+        }
+
+        let items: Vec<&str> = choices.iter().map(|c| c.label()).collect();
+
+        Title {
+            menu: Menu::new(&items),
+            choices,
+            error: None,
+        }
+    }
+
+    //
+    // Loads the saved game and deletes it, so each save is continued once.
+    //
+    fn resume(ctx: &Context) -> Result<Playing, SnakeError> {
+        let state =
+            SaveGame::load()?.ok_or_else(|| SnakeError::new("The saved game has gone missing."))?;
+        let playing = Playing::resume(ctx, state)?;
+
+        SaveGame::remove()?;
+
+        Ok(playing)
+    }
+}
+
+impl Default for Title {
+    fn default() -> Self {
+        Title::new()
+    }
+}
+
+impl Scene<Context> for Title {
+    fn handle_key(
+        &mut self,
+        ctx: &mut Context,
+        key: KeyEvent,
+    ) -> Result<Transition<Context>, Box<dyn Error>> {
+        if let Some(mut dialog) = self.error.take() {
+            if dialog.handle_key(&key) == WidgetEvent::Ignored {
+                self.error = Some(dialog);
+            }
+
+            return Ok(Transition::None);
+        }
+
+        Ok(match self.menu.handle_key(&ctx.menu_key(key)) {
+            WidgetEvent::Submitted => match self.choices[self.menu.selected()] {
+                Choice::Continue => match Title::resume(ctx) {
+                    Ok(playing) => Transition::Switch(Box::new(playing)),
+                    Err(e) => {
+                        self.error =
+                            Some(Dialog::new("Couldn't continue", &e.to_string(), &["OK"]));
+                        Transition::None
+                    }
+                },
+                Choice::Start => Transition::Switch(Box::new(Playing::new(ctx)?)),
+                Choice::Campaign => Transition::Switch(Box::new(CampaignScene::new(ctx)?)),
+                Choice::HighScores => {
+                    Transition::Push(Box::new(HighScoresScene::new(ctx, None, None)))
+                }
+                Choice::Options => Transition::Push(Box::new(OptionsScene::new(ctx))),
+                Choice::Exit => Transition::Quit,
+            },
+            WidgetEvent::Cancelled => Transition::Quit,
+            _ => Transition::None,
+        })
+    }
+
+    fn render(&mut self, _ctx: &Context, r: &mut dyn Renderer) -> Result<(), Box<dyn Error>> {
+        draw_menu_screen(r, "terminal_snake", &["Welcome!"], &self.menu)?;
+
+        if let Some(dialog) = &self.error {
+            dialog.render(r)?;
+        }
+
+        Ok(())
+    }
+}
+
+//
+// Draws a titled panel in the middle of the screen holding a few lines of
+// text, then a gap, then the menu.
+//
+pub fn draw_menu_screen(
+    r: &mut dyn Renderer,
+    title: &str,
+    lines: &[&str],
+    menu: &Menu,
+) -> Result<(), SnengineError> {
+    let (cols, rows) = r.size();
+
+    let widest = lines
+        .iter()
+        .map(|l| l.chars().count() as u16)
+        .chain([title.chars().count() as u16 + 2, menu.width()])
+        .max()
+        .unwrap_or(0);
+    let height = lines.len() as u16 + 1 + menu.height();
+
+    let area = Rect::centered(Rect::new(1, 1, cols, rows), widest + 4, height + 2);
+    let inner = area.inner();
+
+    Panel::titled(title).render(r, area)?;
+
+    for (i, line) in lines.iter().enumerate() {
+        Label::centered(line).render(r, Rect::new(inner.x, inner.y + i as u16, inner.width, 1))?;
+    }
+
+    let menu_y = inner.y + lines.len() as u16 + 1;
+    let menu_area = Rect::centered(
+        Rect::new(inner.x, menu_y, inner.width, inner.bottom() + 1 - menu_y),
+        menu.width(),
+        menu.height(),
+    );
+
+    menu.render(r, menu_area)
+}
@@ -1,6 +1,17 @@
 pub mod arena;
+pub mod board;
+pub mod campaign;
+pub mod controls;
+pub mod difficulty;
 pub mod directions;
 pub mod food;
 pub mod gamestate;
+pub mod goal;
+pub mod highscores;
+pub mod level;
+pub mod mode;
+pub mod progress;
+pub mod save;
 pub mod snake;
 pub mod snake_coords;
+pub mod tick;
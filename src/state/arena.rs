@@ -1,3 +1,5 @@
+use std::collections::HashSet;
+
 use crate::{
     engine::{coords::Coords, unicode::Unicode},
     error::SnakeError,
@@ -12,12 +14,41 @@ pub struct Arena {
 }
 
 impl Arena {
+    /// Where a loaded arena's top left corner goes before it's laid out,
+    /// just below the score. Levels and saved games both start from here.
+    pub const ORIGIN: (i16, i16) = (1, 3);
+
     pub fn new() -> Self {
         Arena {
             positions: Vec::new(),
         }
     }
 
+    //
+    // Builds an arena from the cells that are walls, each drawn with the box
+    // drawing piece that joins it to the walls around it.
+    //
+    pub fn from_walls(cells: &[Coords]) -> Arena {
+        let walls: HashSet<(i16, i16)> = cells.iter().map(|c| (c.x, c.y)).collect();
+        let wall = |x: i16, y: i16| walls.contains(&(x, y));
+
+        let positions = cells
+            .iter()
+            .map(|c| {
+                let glyph = Unicode::joining(
+                    wall(c.x, c.y - 1),
+                    wall(c.x, c.y + 1),
+                    wall(c.x - 1, c.y),
+                    wall(c.x + 1, c.y),
+                );
+
+                (*c, glyph)
+            })
+            .collect();
+
+        Arena { positions }
+    }
+
     pub fn create_level_1(mut state: GameState) -> GameState {
         let (cols, rows) = state.c_dimensions.to_unsigned_tuple();
 
@@ -26,62 +57,53 @@ impl Arena {
 
         // Todo: we need some way of tracking where other blocks have been drawn on
         // so we don't have to manually track stuff like this starting on row 3...
-
-        // Corners
-        state
-            .arena
-            .positions
-            .push((Coords::new(cols - 1, 3), Unicode::BoxLightArcDownAndLeft));
-        state.arena.positions.push((
-            Coords::new(cols - 1, rows - 1),
-            Unicode::BoxLightArcUpAndLeft,
-        ));
-        state
-            .arena
-            .positions
-            .push((Coords::new(1, rows - 1), Unicode::BoxLightArcUpAndRight));
-        state
-            .arena
-            .positions
-            .push((Coords::new(1, 3), Unicode::BoxLightArcDownAndRight));
+        let mut cells = Vec::new();
 
         // Top and bottom lines
-        for i in 2..cols - 1 {
-            state
-                .arena
-                .positions
-                .push((Coords::new(i, 3), Unicode::BoxLightHorizontal));
-            state
-                .arena
-                .positions
-                .push((Coords::new(i, rows - 1), Unicode::BoxLightHorizontal));
+        for i in 1..cols {
+            cells.push(Coords::new(i, 3));
+            cells.push(Coords::new(i, rows - 1));
         }
 
         // Right and left lines
         for i in 4..rows - 1 {
-            state
-                .arena
-                .positions
-                .push((Coords::new(cols - 1, i), Unicode::BoxLightVertical));
-            state
-                .arena
-                .positions
-                .push((Coords::new(1, i), Unicode::BoxLightVertical));
+            cells.push(Coords::new(cols - 1, i));
+            cells.push(Coords::new(1, i));
         }
 
+        state.arena = Arena::from_walls(&cells);
+        state.sync_board();
+
         state
     }
 
     pub fn middle_coords(arena: &Arena) -> Result<(u16, u16), SnakeError> {
-        let x_max = Arena::max_x(arena)?;
-        let y_max = Arena::max_y(arena)?;
+        let (x_min, y_min) = Arena::min_arena_coords(arena)?;
+        let (x_max, y_max) = Arena::max_arena_coords(arena)?;
 
-        let x_middle = (x_max + 1) / 2;
-        let y_middle = (y_max + 1) / 2;
+        let x_middle = (x_min + x_max).div_ceil(2);
+        let y_middle = (y_min + y_max).div_ceil(2);
 
         Ok((x_middle, y_middle))
     }
 
+    pub fn min_arena_coords(arena: &Arena) -> Result<(u16, u16), SnakeError> {
+        let min_x = Arena::min_x(arena)?;
+        let min_y = Arena::min_y(arena)?;
+
+        Ok((min_x, min_y))
+    }
+
+    //
+    // Width and height of the arena, including the walls.
+    //
+    pub fn size(arena: &Arena) -> Result<(u16, u16), SnakeError> {
+        let (min_x, min_y) = Arena::min_arena_coords(arena)?;
+        let (max_x, max_y) = Arena::max_arena_coords(arena)?;
+
+        Ok((max_x - min_x + 1, max_y - min_y + 1))
+    }
+
     pub fn max_arena_coords(arena: &Arena) -> Result<(u16, u16), SnakeError> {
         let max_x = Arena::max_x(arena)?;
         let max_y = Arena::max_y(arena)?;
@@ -97,7 +119,31 @@ impl Arena {
             .max()
         {
             Some(n) => Ok(n),
-            None => Err(SnakeError),
+            None => Err(SnakeError::new("The arena has no walls")),
+        }
+    }
+
+    fn min_x(arena: &Arena) -> Result<u16, SnakeError> {
+        match arena
+            .positions
+            .iter()
+            .map(|pos| pos.0.to_unsigned_tuple().0)
+            .min()
+        {
+            Some(n) => Ok(n),
+            None => Err(SnakeError::new("The arena has no walls")),
+        }
+    }
+
+    fn min_y(arena: &Arena) -> Result<u16, SnakeError> {
+        match arena
+            .positions
+            .iter()
+            .map(|pos| pos.0.to_unsigned_tuple().1)
+            .min()
+        {
+            Some(n) => Ok(n),
+            None => Err(SnakeError::new("The arena has no walls")),
         }
     }
 
@@ -109,7 +155,7 @@ impl Arena {
             .max()
         {
             Some(n) => Ok(n),
-            None => Err(SnakeError),
+            None => Err(SnakeError::new("The arena has no walls")),
         }
     }
 }
@@ -119,3 +165,72 @@ impl Default for Arena {
         Arena::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::engine::renderer::{HeadlessRenderer, Renderer};
+
+    use super::*;
+
+    //
+    // Draws walls marked with `#` in the given rows.
+    //
+    fn draw(map: &[&str]) -> String {
+        let cells: Vec<Coords> = map
+            .iter()
+            .enumerate()
+            .flat_map(|(y, row)| {
+                row.chars()
+                    .enumerate()
+                    .filter(|(_, c)| *c == '#')
+                    .map(move |(x, _)| Coords::new(x as i16 + 1, y as i16 + 1))
+            })
+            .collect();
+
+        let mut r = HeadlessRenderer::new(map[0].len() as u16, map.len() as u16);
+
+        for (c, glyph) in Arena::from_walls(&cells).positions {
+            let (x, y) = c.to_unsigned_tuple();
+            r.draw_char(x, y, glyph).unwrap();
+        }
+
+        r.snapshot()
+    }
+
+    #[test]
+    fn from_walls_joins_neighbours() {
+        let map = [
+            "#######", //
+            "#  #  #", "# ### #", "#  #  #", "####  #", "#   # #", "#######",
+        ];
+
+        assert_eq!(
+            draw(&map),
+            [
+                "╭──┬──╮",
+                "│  │  │",
+                "│ ─┼─ │",
+                "│  │  │",
+                "├──╯  │",
+                "│   │ │",
+                "╰───┴─╯",
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn from_walls_marks_a_lone_wall() {
+        assert_eq!(draw(&["   ", " # ", "   "]), ["", " ■", ""].join("\n"));
+    }
+
+    #[test]
+    fn create_level_1_is_a_rounded_box() {
+        let mut state = GameState::new();
+        state.c_dimensions = Coords::new(12, 14);
+        state = Arena::create_level_1(state);
+
+        assert_eq!(Arena::size(&state.arena), Ok((5, 4)));
+        assert_eq!(state.arena.positions.len(), 14);
+    }
+}
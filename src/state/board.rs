@@ -0,0 +1,260 @@
+use crate::{engine::coords::Coords, random::random::Random};
+
+use super::{arena::Arena, food::Food, snake::Snake};
+
+// How many random cells food placement tries before looking at every cell.
+const PLACE_ATTEMPTS: usize = 32;
+
+/// What's in a cell of the board.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Cell {
+    Empty,
+    /// Part of the arena's outer edge.
+    Wall,
+    /// A wall inside the arena.
+    Obstacle,
+    Food,
+    /// At least one of snake's blocks.
+    Snake,
+}
+
+/// A grid over the arena recording what's in every cell, so collisions and
+/// free cells are looked up rather than searched for. It's kept up to date
+/// as snake moves and food comes and goes.
+#[derive(Debug, Clone, Default)]
+pub struct Board {
+    // The arena's top left corner
+    origin: Coords,
+    width: u16,
+    height: u16,
+
+    // Walls, obstacles and food, row by row
+    cells: Vec<Cell>,
+
+    // How many of snake's blocks are in each cell. It's more than one for a
+    // moment when he runs into himself.
+    snake: Vec<u16>,
+}
+
+impl Board {
+    //
+    // Fills in a board from scratch. With no arena there's no board, and
+    // everything is treated as a wall.
+    //
+    pub fn build(arena: &Arena, snake: &Snake, food: &Food) -> Board {
+        let (origin, (width, height)) = match (Arena::min_arena_coords(arena), Arena::size(arena)) {
+            (Ok((x, y)), Ok(size)) => (Coords::new(x as i16, y as i16), size),
+            _ => return Board::default(),
+        };
+
+        let area = width as usize * height as usize;
+        let mut board = Board {
+            origin,
+            width,
+            height,
+            cells: vec![Cell::Empty; area],
+            snake: vec![0; area],
+        };
+
+        for (c, _) in &arena.positions {
+            let edge = c.x == origin.x
+                || c.y == origin.y
+                || c.x == origin.x + width as i16 - 1
+                || c.y == origin.y + height as i16 - 1;
+
+            if let Some(i) = board.index(*c) {
+                board.cells[i] = if edge { Cell::Wall } else { Cell::Obstacle };
+            }
+        }
+
+        for f in &food.items {
+            board.set_food(f.coords, true);
+        }
+
+        for p in &snake.positions {
+            board.add_snake(p.coords);
+        }
+
+        board
+    }
+
+    fn index(&self, c: Coords) -> Option<usize> {
+        let x = c.x - self.origin.x;
+        let y = c.y - self.origin.y;
+
+        match x >= 0 && y >= 0 && x < self.width as i16 && y < self.height as i16 {
+            true => Some(y as usize * self.width as usize + x as usize),
+            false => None,
+        }
+    }
+
+    /// What's at the given coordinates. Snake is reported over anything
+    /// else there, and anywhere outside the arena counts as a wall.
+    pub fn at(&self, c: Coords) -> Cell {
+        match self.index(c) {
+            Some(i) if self.snake[i] > 0 => Cell::Snake,
+            Some(i) => self.cells[i],
+            None => Cell::Wall,
+        }
+    }
+
+    pub fn is_empty(&self, c: Coords) -> bool {
+        self.at(c) == Cell::Empty
+    }
+
+    pub fn add_snake(&mut self, c: Coords) {
+        if let Some(i) = self.index(c) {
+            self.snake[i] += 1;
+        }
+    }
+
+    pub fn remove_snake(&mut self, c: Coords) {
+        if let Some(i) = self.index(c) {
+            self.snake[i] = self.snake[i].saturating_sub(1);
+        }
+    }
+
+    pub fn set_food(&mut self, c: Coords, food: bool) {
+        if let Some(i) = self.index(c) {
+            self.cells[i] = match (food, self.cells[i]) {
+                (true, Cell::Empty) => Cell::Food,
+                (false, Cell::Food) => Cell::Empty,
+                (_, cell) => cell,
+            };
+        }
+    }
+
+    /// Moves the board along with everything on it.
+    pub fn translate(&mut self, dx: i16, dy: i16) {
+        self.origin.x += dx;
+        self.origin.y += dy;
+    }
+
+    /// Every empty cell, from the top left, row by row.
+    pub fn empty_cells(&self) -> Vec<Coords> {
+        (0..self.cells.len())
+            .filter(|&i| self.cells[i] == Cell::Empty && self.snake[i] == 0)
+            .map(|i| {
+                Coords::new(
+                    self.origin.x + (i % self.width as usize) as i16,
+                    self.origin.y + (i / self.width as usize) as i16,
+                )
+            })
+            .collect()
+    }
+
+    /// An empty cell picked at random, every one being equally likely.
+    /// Random cells are tried first, which nearly always finds one straight
+    /// away, and only a crowded board has to be searched.
+    pub fn random_empty(&self, rng: &mut Random) -> Option<Coords> {
+        if self.cells.is_empty() {
+            return None;
+        }
+
+        for _ in 0..PLACE_ATTEMPTS {
+            let i = rng.below(self.cells.len());
+            let c = Coords::new(
+                self.origin.x + (i % self.width as usize) as i16,
+                self.origin.y + (i / self.width as usize) as i16,
+            );
+
+            if self.is_empty(c) {
+                return Some(c);
+            }
+        }
+
+        rng.choose(&self.empty_cells()).copied()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        engine::unicode::Unicode,
+        state::{
+            directions::Directions,
+            food::{FoodItem, FoodKind},
+            gamestate::GameState,
+            snake_coords::SnakeCoords,
+        },
+    };
+
+    use super::*;
+
+    //
+    // A 5x4 level 1 arena, with a wall added inside, a two block snake and
+    // a piece of food.
+    //
+    fn set_state() -> GameState {
+        let mut state = GameState::level_1(Coords::new(12, 14));
+
+        state
+            .arena
+            .positions
+            .push((Coords::new(4, 4), Unicode::BlackSquare));
+        state.snake.positions = vec![
+            SnakeCoords::new(2, 4, Directions::Right, true),
+            SnakeCoords::new(2, 5, Directions::Up, true),
+        ];
+        state
+            .food
+            .items
+            .push(FoodItem::new(Coords::new(3, 5), FoodKind::Apple));
+        state.sync_board();
+
+        state
+    }
+
+    #[test]
+    fn build_records_everything() {
+        let state = set_state();
+
+        assert_eq!(state.board.at(Coords::new(1, 3)), Cell::Wall);
+        assert_eq!(state.board.at(Coords::new(4, 4)), Cell::Obstacle);
+        assert_eq!(state.board.at(Coords::new(2, 4)), Cell::Snake);
+        assert_eq!(state.board.at(Coords::new(3, 5)), Cell::Food);
+        assert_eq!(state.board.at(Coords::new(3, 4)), Cell::Empty);
+        assert_eq!(state.board.at(Coords::new(0, 0)), Cell::Wall);
+        assert_eq!(
+            state.board.empty_cells(),
+            vec![Coords::new(3, 4), Coords::new(4, 5)]
+        );
+    }
+
+    #[test]
+    fn snake_blocks_are_counted() {
+        let mut board = set_state().board;
+        let c = Coords::new(3, 4);
+
+        board.add_snake(c);
+        board.add_snake(c);
+        board.remove_snake(c);
+        assert_eq!(board.at(c), Cell::Snake);
+
+        board.remove_snake(c);
+        assert_eq!(board.at(c), Cell::Empty);
+    }
+
+    #[test]
+    fn translate_moves_the_board() {
+        let mut board = set_state().board;
+
+        board.translate(10, 1);
+
+        assert_eq!(board.at(Coords::new(14, 5)), Cell::Obstacle);
+        assert_eq!(board.at(Coords::new(4, 4)), Cell::Wall);
+    }
+
+    #[test]
+    fn random_empty_only_finds_empty_cells() {
+        let state = set_state();
+        let mut rng = Random::seeded(3);
+
+        for _ in 0..50 {
+            let c = state.board.random_empty(&mut rng).unwrap();
+            assert!(c == Coords::new(3, 4) || c == Coords::new(4, 5));
+        }
+
+        assert_eq!(Board::default().random_empty(&mut rng), None);
+    }
+}
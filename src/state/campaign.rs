@@ -0,0 +1,61 @@
+use crate::error::SnakeError;
+
+use super::level::Level;
+
+// The campaign's levels in the order they're played, built into the game.
+const LEVELS: [&str; 4] = [
+    include_str!("../../levels/01-first-steps.level"),
+    include_str!("../../levels/02-pillars.level"),
+    include_str!("../../levels/03-crossroads.level"),
+    include_str!("../../levels/04-maze.level"),
+];
+
+/// The built-in levels, each unlocked by completing the one before.
+pub struct Campaign;
+
+impl Campaign {
+    pub fn count() -> usize {
+        LEVELS.len()
+    }
+
+    /// The level at the given position, counting from 0.
+    pub fn level(index: usize) -> Result<Level, SnakeError> {
+        let text = LEVELS
+            .get(index)
+            .ok_or_else(|| SnakeError::new(&format!("There's no campaign level {}", index + 1)))?;
+
+        Level::parse(text)
+            .map_err(|e| SnakeError::new(&format!("Campaign level {}: {}", index + 1, e)))
+    }
+
+    pub fn levels() -> Result<Vec<Level>, SnakeError> {
+        (0..Campaign::count()).map(Campaign::level).collect()
+    }
+
+    pub fn is_last(index: usize) -> bool {
+        index + 1 >= Campaign::count()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_level_loads_with_a_goal() {
+        let levels = Campaign::levels().unwrap();
+
+        assert_eq!(levels.len(), Campaign::count());
+        assert!(levels.iter().all(|l| l.goal.is_some()));
+    }
+
+    #[test]
+    fn level_past_the_end() {
+        assert_eq!(
+            Campaign::level(Campaign::count()).err(),
+            Some(SnakeError::new("There's no campaign level 5"))
+        );
+        assert!(Campaign::is_last(Campaign::count() - 1));
+        assert!(!Campaign::is_last(0));
+    }
+}
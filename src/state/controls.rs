@@ -0,0 +1,432 @@
+use std::collections::HashMap;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::PathBuf;
+
+use crate::{
+    engine::{
+        inputhandler::{KeyCode, KeyEvent},
+        paths::Paths,
+    },
+    error::SnakeError,
+};
+
+use super::{directions::Directions, snake::Snake};
+
+/// Everything the player can ask the game to do.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Action {
+    Turn(Directions),
+    Pause,
+    Quit,
+    Boost,
+    /// Saves the game to continue later, then leaves it.
+    SaveQuit,
+}
+
+impl Action {
+    pub const ALL: [Action; 8] = [
+        Action::Turn(Directions::Up),
+        Action::Turn(Directions::Down),
+        Action::Turn(Directions::Left),
+        Action::Turn(Directions::Right),
+        Action::Pause,
+        Action::Quit,
+        Action::Boost,
+        Action::SaveQuit,
+    ];
+
+    /// The name used for the action in the key bindings file.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Action::Turn(Directions::Up) => "up",
+            Action::Turn(Directions::Down) => "down",
+            Action::Turn(Directions::Left) => "left",
+            Action::Turn(Directions::Right) => "right",
+            Action::Turn(Directions::None) => "none",
+            Action::Pause => "pause",
+            Action::Quit => "quit",
+            Action::Boost => "boost",
+            Action::SaveQuit => "save",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Action> {
+        Action::ALL.into_iter().find(|a| a.name() == name)
+    }
+}
+
+/// Maps the key events coming out of the engine to game `Action`s.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Controls {
+    bindings: HashMap<KeyEvent, Action>,
+}
+
+impl Controls {
+    /// Creates controls with nothing bound.
+    pub fn new() -> Self {
+        Controls {
+            bindings: HashMap::new(),
+        }
+    }
+
+    pub fn bind(&mut self, key: KeyEvent, action: Action) -> &mut Controls {
+        self.bindings.insert(key, action);
+        self
+    }
+
+    pub fn action_for(&self, key: &KeyEvent) -> Option<Action> {
+        self.bindings.get(key).copied()
+    }
+
+    /// One of the keys bound to the action, for showing in hints. When there
+    /// are several, the one with the shortest name wins.
+    pub fn key_for(&self, action: Action) -> Option<KeyEvent> {
+        self.bindings
+            .iter()
+            .filter(|(_, a)| **a == action)
+            .map(|(k, _)| *k)
+            .min_by_key(|k| {
+                let name = k.to_string();
+                (name.chars().count(), name)
+            })
+    }
+
+    /// Maps each key to its action, dropping keys that aren't bound.
+    pub fn actions(&self, keys: &[KeyEvent]) -> Vec<Action> {
+        keys.iter().filter_map(|k| self.action_for(k)).collect()
+    }
+
+    /// Where the key bindings file lives, e.g.
+    /// `~/.config/terminal_snake/keys.conf`.
+    pub fn config_path() -> Option<PathBuf> {
+        Paths::config_dir(env!("CARGO_PKG_NAME")).map(|dir| dir.join("keys.conf"))
+    }
+
+    /// Loads the key bindings file, or the defaults if there isn't one.
+    pub fn load() -> Result<Controls, SnakeError> {
+        let path = match Controls::config_path() {
+            Some(path) => path,
+            None => return Ok(Controls::default()),
+        };
+
+        match fs::read_to_string(&path) {
+            Ok(text) => Controls::from_config(&text)
+                .map_err(|e| SnakeError::new(&format!("{}: {}", path.display(), e))),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(Controls::default()),
+            Err(e) => Err(SnakeError::new(&format!(
+                "Couldn't read {}: {}",
+                path.display(),
+                e
+            ))),
+        }
+    }
+
+    /// Parses a key bindings file. Each line binds an action to one or more
+    /// comma separated keys, and blank lines and `#` comments are ignored:
+    ///
+    /// ```text
+    /// # Dvorak
+    /// up = Up, comma
+    /// pause = p, Esc
+    /// ```
+    ///
+    /// Actions which aren't mentioned keep their default keys. Binding the
+    /// same key to two different actions is an error.
+    pub fn from_config(text: &str) -> Result<Controls, SnakeError> {
+        let error = |line_no: usize, message: String| {
+            SnakeError::new(&format!("line {}: {}", line_no, message))
+        };
+
+        // Read every line first, so which defaults go doesn't depend on the
+        // order actions are listed in.
+        let mut configured: Vec<(usize, Action, Vec<KeyEvent>)> = Vec::new();
+
+        for (i, line) in text.lines().enumerate() {
+            let line_no = i + 1;
+            let line = line.trim();
+
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let (name, keys) = match line.split_once('=') {
+                Some(pair) => pair,
+                None => {
+                    return Err(error(
+                        line_no,
+                        format!("expected `action = key, ...`, found '{}'", line),
+                    ))
+                }
+            };
+
+            let name = name.trim();
+            let action = match Action::from_name(name) {
+                Some(action) => action,
+                None => return Err(error(line_no, format!("unknown action '{}'", name))),
+            };
+
+            if configured.iter().any(|(_, a, _)| *a == action) {
+                return Err(error(line_no, format!("'{}' is set more than once", name)));
+            }
+
+            let keys = keys
+                .split(',')
+                .map(|key| key.trim().parse::<KeyEvent>())
+                .collect::<Result<Vec<KeyEvent>, _>>()
+                .map_err(|e| error(line_no, format!("{}", e)))?;
+
+            configured.push((line_no, action, keys));
+        }
+
+        // Every action that's configured loses its defaults.
+        let mut controls = Controls::default();
+        controls
+            .bindings
+            .retain(|_, a| !configured.iter().any(|(_, c, _)| c == a));
+
+        for (line_no, action, keys) in configured {
+            for event in keys {
+                match controls.action_for(&event) {
+                    Some(other) if other != action => {
+                        return Err(error(
+                            line_no,
+                            format!(
+                                "'{}' is bound to both {} and {}",
+                                event,
+                                other.name(),
+                                action.name()
+                            ),
+                        ));
+                    }
+                    _ => {
+                        controls.bind(event, action);
+                    }
+                }
+            }
+        }
+
+        Ok(controls)
+    }
+
+    //
+    // Queues a turn for snake, unless it would send him back on himself or
+    // wouldn't change anything. The check is against the direction he'll be
+    // facing on the tick the turn is taken, so quick Up-Left presses while
+    // heading Right make a tight U-turn. Returns true if the turn was queued.
+    //
+    // A cool Becca feature: instead of stopping the player going back on
+    // themselves, if they do, could the snake just loop back on itself.
+    // Would it go up on Y or down on Y when it backtracks left and right...?
+    //
+    pub fn turn(snake: &mut Snake, direction: Directions) -> bool {
+        let facing = snake.next_direction();
+
+        if direction == Directions::None || direction == facing || direction == facing.opposite() {
+            return false;
+        }
+
+        snake.queue_turn(direction)
+    }
+}
+
+impl Default for Controls {
+    //
+    // Arrows, WASD and vim's hjkl all steer at the same time.
+    //
+    fn default() -> Self {
+        let mut controls = Controls::new();
+
+        let turns = [
+            (
+                Directions::Up,
+                [KeyCode::Up, KeyCode::Char('w'), KeyCode::Char('k')],
+            ),
+            (
+                Directions::Down,
+                [KeyCode::Down, KeyCode::Char('s'), KeyCode::Char('j')],
+            ),
+            (
+                Directions::Left,
+                [KeyCode::Left, KeyCode::Char('a'), KeyCode::Char('h')],
+            ),
+            (
+                Directions::Right,
+                [KeyCode::Right, KeyCode::Char('d'), KeyCode::Char('l')],
+            ),
+        ];
+
+        for (direction, codes) in turns {
+            for code in codes {
+                controls.bind(KeyEvent::new(code), Action::Turn(direction));
+            }
+        }
+
+        controls
+            .bind(KeyEvent::new(KeyCode::Char('p')), Action::Pause)
+            .bind(KeyEvent::new(KeyCode::Char('q')), Action::Quit)
+            .bind(KeyEvent::new(KeyCode::Char(' ')), Action::Boost)
+            .bind(KeyEvent::new(KeyCode::Char('x')), Action::SaveQuit);
+
+        controls
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::engine::inputhandler::Modifiers;
+
+    use super::*;
+
+    #[test]
+    fn default_maps_arrows_wasd_and_hjkl() {
+        let controls = Controls::default();
+
+        for code in [KeyCode::Left, KeyCode::Char('a'), KeyCode::Char('h')] {
+            assert_eq!(
+                controls.action_for(&KeyEvent::new(code)),
+                Some(Action::Turn(Directions::Left))
+            );
+        }
+    }
+
+    #[test]
+    fn key_for_prefers_shortest_name() {
+        let controls = Controls::default();
+
+        assert_eq!(
+            controls.key_for(Action::Turn(Directions::Up)),
+            Some(KeyEvent::new(KeyCode::Char('k')))
+        );
+        assert_eq!(
+            controls.key_for(Action::Pause),
+            Some(KeyEvent::new(KeyCode::Char('p')))
+        );
+    }
+
+    #[test]
+    fn unbound_keys_are_dropped() {
+        let controls = Controls::default();
+        let keys = [
+            KeyEvent::new(KeyCode::Char('z')),
+            KeyEvent::with_modifiers(KeyCode::Char('q'), Modifiers::CTRL),
+            KeyEvent::new(KeyCode::Char('q')),
+        ];
+
+        assert_eq!(controls.actions(&keys), vec![Action::Quit]);
+    }
+
+    #[test]
+    fn turn_queues_direction() {
+        let mut snake = Snake::new();
+        snake.direction = Directions::Up;
+
+        assert!(Controls::turn(&mut snake, Directions::Left));
+        assert_eq!(snake.next_direction(), Directions::Left);
+    }
+
+    #[test]
+    fn turn_does_not_reverse() {
+        let mut snake = Snake::new();
+        snake.direction = Directions::Up;
+
+        assert!(!Controls::turn(&mut snake, Directions::Down));
+        assert_eq!(snake.next_direction(), Directions::Up);
+    }
+
+    #[test]
+    fn turn_checks_against_queued_direction() {
+        let mut snake = Snake::new();
+        snake.direction = Directions::Right;
+
+        // Left straight away would reverse, but after Up it's a U-turn.
+        assert!(!Controls::turn(&mut snake, Directions::Left));
+        assert!(Controls::turn(&mut snake, Directions::Up));
+        assert!(Controls::turn(&mut snake, Directions::Left));
+        assert!(!Controls::turn(&mut snake, Directions::Right));
+
+        assert_eq!(snake.pending_turns, vec![Directions::Up, Directions::Left]);
+    }
+
+    #[test]
+    fn turn_ignores_current_direction() {
+        let mut snake = Snake::new();
+        snake.direction = Directions::Right;
+
+        assert!(!Controls::turn(&mut snake, Directions::Right));
+        assert!(snake.pending_turns.is_empty());
+    }
+
+    #[test]
+    fn config_replaces_bindings_for_listed_actions() {
+        let controls = Controls::from_config("# Dvorak\n\nup = Up, comma\n").unwrap();
+
+        assert_eq!(
+            controls.action_for(&KeyEvent::new(KeyCode::Char(','))),
+            Some(Action::Turn(Directions::Up))
+        );
+        assert_eq!(
+            controls.action_for(&KeyEvent::new(KeyCode::Char('w'))),
+            None
+        );
+        assert_eq!(
+            controls.action_for(&KeyEvent::new(KeyCode::Up)),
+            Some(Action::Turn(Directions::Up))
+        );
+        // Untouched actions keep their defaults.
+        assert_eq!(
+            controls.action_for(&KeyEvent::new(KeyCode::Char('s'))),
+            Some(Action::Turn(Directions::Down))
+        );
+    }
+
+    #[test]
+    fn config_rejects_conflicts() {
+        let result = Controls::from_config("pause = w");
+        let expected = Err(SnakeError::new("line 1: 'w' is bound to both up and pause"));
+        assert_eq!(result, expected);
+
+        let result = Controls::from_config("up = k\npause = k");
+        let expected = Err(SnakeError::new("line 2: 'k' is bound to both up and pause"));
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn config_frees_keys_whatever_the_order() {
+        for text in ["pause = w\nup = comma", "up = comma\npause = w"] {
+            let controls = Controls::from_config(text).unwrap();
+
+            assert_eq!(
+                controls.action_for(&KeyEvent::new(KeyCode::Char('w'))),
+                Some(Action::Pause)
+            );
+            assert_eq!(
+                controls.action_for(&KeyEvent::new(KeyCode::Char(','))),
+                Some(Action::Turn(Directions::Up))
+            );
+        }
+    }
+
+    #[test]
+    fn config_rejects_unknown_action() {
+        let result = Controls::from_config("\njump = Space");
+        let expected = Err(SnakeError::new("line 2: unknown action 'jump'"));
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn config_rejects_unknown_key() {
+        let result = Controls::from_config("quit = Ctrl+Nope");
+        let expected = Err(SnakeError::new("line 1: Unknown key 'Ctrl+Nope'"));
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn config_rejects_malformed_line() {
+        let result = Controls::from_config("up Up");
+        let expected = Err(SnakeError::new(
+            "line 1: expected `action = key, ...`, found 'up Up'",
+        ));
+        assert_eq!(result, expected);
+    }
+}
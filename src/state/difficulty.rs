@@ -0,0 +1,167 @@
+use std::time::Duration;
+
+/// Preset speed settings the player can pick from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Difficulty {
+    Easy,
+    #[default]
+    Normal,
+    Hard,
+    Insane,
+}
+
+/// What makes the snake speed up.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpeedBy {
+    Score,
+    Length,
+}
+
+/// How the tick interval shortens as the game goes on.
+///
+/// Every `per_level` points (or blocks of length) is a level, and each level
+/// takes `step` off the `start` interval until it reaches `fastest`. A
+/// `per_level` of 1 gives a smooth curve, anything higher makes it step-wise.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SpeedCurve {
+    pub start: Duration,
+    pub fastest: Duration,
+    pub step: Duration,
+    pub per_level: u32,
+    pub by: SpeedBy,
+}
+
+impl SpeedCurve {
+    pub fn level(&self, score: u32, length: u32) -> u32 {
+        let progress = match self.by {
+            SpeedBy::Score => score,
+            SpeedBy::Length => length,
+        };
+
+        progress / self.per_level.max(1)
+    }
+
+    pub fn tick_interval(&self, score: u32, length: u32) -> Duration {
+        let faster_by = self.step.saturating_mul(self.level(score, length));
+
+        self.start.saturating_sub(faster_by).max(self.fastest)
+    }
+}
+
+impl Difficulty {
+    pub const ALL: [Difficulty; 4] = [
+        Difficulty::Easy,
+        Difficulty::Normal,
+        Difficulty::Hard,
+        Difficulty::Insane,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Difficulty::Easy => "easy",
+            Difficulty::Normal => "normal",
+            Difficulty::Hard => "hard",
+            Difficulty::Insane => "insane",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Difficulty> {
+        let name = name.to_ascii_lowercase();
+
+        Difficulty::ALL.into_iter().find(|d| d.name() == name)
+    }
+
+    pub fn speed_curve(&self) -> SpeedCurve {
+        let ms = Duration::from_millis;
+
+        match self {
+            Difficulty::Easy => SpeedCurve {
+                start: ms(150),
+                fastest: ms(90),
+                step: ms(2),
+                per_level: 1,
+                by: SpeedBy::Score,
+            },
+            Difficulty::Normal => SpeedCurve {
+                start: ms(100),
+                fastest: ms(55),
+                step: ms(2),
+                per_level: 1,
+                by: SpeedBy::Score,
+            },
+            Difficulty::Hard => SpeedCurve {
+                start: ms(80),
+                fastest: ms(40),
+                step: ms(8),
+                per_level: 5,
+                by: SpeedBy::Score,
+            },
+            Difficulty::Insane => SpeedCurve {
+                start: ms(55),
+                fastest: ms(25),
+                step: ms(5),
+                per_level: 3,
+                by: SpeedBy::Length,
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn curve(per_level: u32) -> SpeedCurve {
+        SpeedCurve {
+            start: Duration::from_millis(100),
+            fastest: Duration::from_millis(60),
+            step: Duration::from_millis(5),
+            per_level,
+            by: SpeedBy::Score,
+        }
+    }
+
+    #[test]
+    fn normal_starts_at_100ms() {
+        assert_eq!(
+            Difficulty::Normal.speed_curve().tick_interval(0, 1),
+            Duration::from_millis(100)
+        );
+    }
+
+    #[test]
+    fn smooth_curve_speeds_up_every_point() {
+        let curve = curve(1);
+
+        assert_eq!(curve.tick_interval(1, 0), Duration::from_millis(95));
+        assert_eq!(curve.tick_interval(4, 0), Duration::from_millis(80));
+    }
+
+    #[test]
+    fn step_wise_curve_speeds_up_every_level() {
+        let curve = curve(5);
+
+        assert_eq!(curve.tick_interval(4, 0), Duration::from_millis(100));
+        assert_eq!(curve.tick_interval(5, 0), Duration::from_millis(95));
+        assert_eq!(curve.tick_interval(12, 0), Duration::from_millis(90));
+    }
+
+    #[test]
+    fn curve_is_capped_at_fastest() {
+        assert_eq!(curve(1).tick_interval(1000, 0), Duration::from_millis(60));
+    }
+
+    #[test]
+    fn curve_can_follow_length() {
+        let mut curve = curve(1);
+        curve.by = SpeedBy::Length;
+
+        assert_eq!(curve.tick_interval(50, 2), Duration::from_millis(90));
+    }
+
+    #[test]
+    fn from_name_ignores_case() {
+        assert_eq!(Difficulty::from_name("Hard"), Some(Difficulty::Hard));
+        assert_eq!(Difficulty::from_name("nightmare"), None);
+    }
+}
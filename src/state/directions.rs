@@ -1,4 +1,4 @@
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Directions {
     None,
     Up,
@@ -7,15 +7,36 @@ pub enum Directions {
     Left,
 }
 
-impl PartialEq for Directions {
-    fn eq(&self, other: &Self) -> bool {
-        match (self, other) {
-            (Directions::None, Directions::None) => true,
-            (Directions::Down, Directions::Down) => true,
-            (Directions::Left, Directions::Left) => true,
-            (Directions::Right, Directions::Right) => true,
-            (Directions::Up, Directions::Up) => true,
-            _ => false,
+impl Directions {
+    pub const ALL: [Directions; 5] = [
+        Directions::None,
+        Directions::Up,
+        Directions::Down,
+        Directions::Right,
+        Directions::Left,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Directions::None => "none",
+            Directions::Up => "up",
+            Directions::Down => "down",
+            Directions::Right => "right",
+            Directions::Left => "left",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Directions> {
+        Directions::ALL.into_iter().find(|d| d.name() == name)
+    }
+
+    pub fn opposite(&self) -> Directions {
+        match self {
+            Directions::Up => Directions::Down,
+            Directions::Down => Directions::Up,
+            Directions::Left => Directions::Right,
+            Directions::Right => Directions::Left,
+            Directions::None => Directions::None,
         }
     }
 }
@@ -1,37 +1,429 @@
 use crate::{
-    engine::coords::Coords, error::SnakeError, random::random::Random, state::arena::Arena,
+    engine::{coords::Coords, graphics::Color, unicode::Unicode},
+    error::SnakeError,
 };
 
 use super::gamestate::GameState;
 
+// The chance each step of a bonus turning up, when there isn't one already.
+const BONUS_CHANCE: f64 = 0.02;
+
+// How many steps before a bonus disappears its glyph changes as a warning.
+const BONUS_WARNING: u16 = 10;
+
+/// What a piece of food is, which decides how it looks and what eating it
+/// does to snake.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FoodKind {
+    /// The everyday food.
+    Apple,
+    /// A bonus worth a lot, but makes snake much longer. It only turns up
+    /// now and then, and not for long.
+    Golden,
+    /// Scores a little and takes blocks off snake's tail.
+    Berry,
+}
+
+impl FoodKind {
+    pub const ALL: [FoodKind; 3] = [FoodKind::Apple, FoodKind::Golden, FoodKind::Berry];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            FoodKind::Apple => "apple",
+            FoodKind::Golden => "golden",
+            FoodKind::Berry => "berry",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<FoodKind> {
+        FoodKind::ALL.into_iter().find(|k| k.name() == name)
+    }
+
+    pub fn glyph(&self) -> Unicode {
+        match self {
+            FoodKind::Apple => Unicode::HeavyCircleWithCircleInside,
+            FoodKind::Golden => Unicode::BlackStar,
+            FoodKind::Berry => Unicode::BlackCircle,
+        }
+    }
+
+    pub fn color(&self) -> Color {
+        match self {
+            FoodKind::Apple => Color::Red,
+            FoodKind::Golden => Color::Yellow,
+            FoodKind::Berry => Color::Magenta,
+        }
+    }
+
+    /// How much eating it adds to the score.
+    pub fn points(&self) -> i16 {
+        match self {
+            FoodKind::Apple => 1,
+            FoodKind::Golden => 5,
+            FoodKind::Berry => 2,
+        }
+    }
+
+    /// How many blocks snake grows by, or loses when it's negative.
+    pub fn growth(&self) -> i16 {
+        match self {
+            FoodKind::Apple => 1,
+            FoodKind::Golden => 3,
+            FoodKind::Berry => -2,
+        }
+    }
+
+    /// How often it turns up compared to the other kinds when the arena is
+    /// topped up. Bonuses aren't part of that, they turn up on their own.
+    pub fn weight(&self) -> u32 {
+        match self {
+            FoodKind::Apple => 90,
+            FoodKind::Golden => 0,
+            FoodKind::Berry => 10,
+        }
+    }
+
+    /// How many steps it stays in the arena, if it doesn't stay until eaten.
+    pub fn lifetime(&self) -> Option<u16> {
+        match self {
+            FoodKind::Golden => Some(50),
+            _ => None,
+        }
+    }
+}
+
+/// A single piece of food in the arena.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FoodItem {
+    pub coords: Coords,
+    pub kind: FoodKind,
+    /// Steps left until it disappears, for food that doesn't last.
+    pub ticks_left: Option<u16>,
+}
+
+impl FoodItem {
+    pub fn new(coords: Coords, kind: FoodKind) -> Self {
+        FoodItem {
+            coords,
+            kind,
+            ticks_left: kind.lifetime(),
+        }
+    }
+
+    /// True when it's about to disappear.
+    pub fn is_expiring(&self) -> bool {
+        self.ticks_left.is_some_and(|t| t <= BONUS_WARNING)
+    }
+
+    /// What it looks like right now, which changes as it's about to go.
+    pub fn glyph(&self) -> Unicode {
+        match (self.kind, self.is_expiring()) {
+            (FoodKind::Golden, true) => Unicode::WhiteStar,
+            (kind, _) => kind.glyph(),
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct Food {
-    pub positions: [Coords; 3],
+    // Everything in the arena waiting to be eaten
+    pub items: Vec<FoodItem>,
+
+    // How many items the arena is kept topped up to
+    pub count: usize,
+
+    // Whether timed bonuses turn up
+    pub bonus: bool,
+}
+
+/// Where a new piece of food ended up.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[must_use]
+pub enum Placement {
+    Placed(FoodItem),
+    /// Snake fills every cell the food could go in, so he's won.
+    BoardFull,
 }
 
 impl Food {
-    pub fn new_random(mut state: GameState, count: i8) -> Result<GameState, SnakeError> {
-        let (cols, rows) = Arena::max_arena_coords(&state.arena)?;
-        let rand_cols = Random::time_seed().get(2, (cols - 1).into()) as i16;
-        let rand_rows = Random::time_seed().get(4, (rows - 1).into()) as i16;
+    pub fn new(count: usize) -> Self {
+        Food {
+            items: Vec::with_capacity(count),
+            count,
+            bonus: true,
+        }
+    }
+
+    //
+    // The bonus in the arena, if there is one.
+    //
+    pub fn bonus(&self) -> Option<&FoodItem> {
+        self.items.iter().find(|f| f.ticks_left.is_some())
+    }
+
+    //
+    // The food at the given coordinates, if there is any.
+    //
+    pub fn at(&self, coords: Coords) -> Option<usize> {
+        self.items.iter().position(|f| f.coords == coords)
+    }
+
+    //
+    // Adds a piece of food of a randomly picked kind to a cell picked at
+    // random from the free ones, every free cell being equally likely.
+    //
+    pub fn place(state: &mut GameState) -> Result<Placement, SnakeError> {
+        let weights = FoodKind::ALL.map(|k| (k, k.weight()));
+        let kind = *state.rng.weighted(&weights).unwrap_or(&FoodKind::Apple);
+
+        Food::place_kind(state, kind)
+    }
+
+    //
+    // Adds a piece of food of the given kind to a random free cell.
+    //
+    pub fn place_kind(state: &mut GameState, kind: FoodKind) -> Result<Placement, SnakeError> {
+        let item = match state.board.random_empty(&mut state.rng) {
+            Some(coords) => FoodItem::new(coords, kind),
+            None => return Ok(Placement::BoardFull),
+        };
+
+        state.food.items.push(item);
+        state.board.set_food(item.coords, true);
+
+        Ok(Placement::Placed(item))
+    }
+
+    //
+    // Takes the food at the given index out of the arena, e.g. once it's
+    // been eaten.
+    //
+    pub fn remove(state: &mut GameState, index: usize) -> FoodItem {
+        let item = state.food.items.remove(index);
+        state.board.set_food(item.coords, false);
 
-        if (count as usize) > state.food.positions.len() {
-            return Err(SnakeError);
+        item
+    }
+
+    //
+    // Places food until the arena holds `count` lasting items, or there's no
+    // room for any more. The board only counts as full once there's no food
+    // left for snake to eat either.
+    //
+    pub fn fill(state: &mut GameState) -> Result<Placement, SnakeError> {
+        let lasting = |food: &Food| food.items.iter().filter(|f| f.ticks_left.is_none()).count();
+
+        while lasting(&state.food) < state.food.count {
+            if Food::place(state)? == Placement::BoardFull {
+                break;
+            }
+        }
+
+        match state.food.items.last() {
+            Some(&item) => Ok(Placement::Placed(item)),
+            None => Ok(Placement::BoardFull),
+        }
+    }
+
+    //
+    // Counts down food that doesn't last, taking away whatever has run out,
+    // then maybe puts out a new bonus. Returns the food that ran out.
+    //
+    pub fn tick(state: &mut GameState) -> Result<Vec<FoodItem>, SnakeError> {
+        for f in state.food.items.iter_mut() {
+            if let Some(t) = f.ticks_left.as_mut() {
+                *t = t.saturating_sub(1);
+            }
         }
 
-        // Tried to spawn food on top of part of Snake.
-        if state
-            .snake
+        let (expired, items): (Vec<FoodItem>, Vec<FoodItem>) = state
+            .food
+            .items
+            .iter()
+            .partition(|f| f.ticks_left == Some(0));
+        state.food.items = items;
+
+        for f in &expired {
+            state.board.set_food(f.coords, false);
+        }
+
+        if state.food.bonus && state.food.bonus().is_none() && state.rng.chance(BONUS_CHANCE) {
+            let _ = Food::place_kind(state, FoodKind::Golden)?;
+        }
+
+        Ok(expired)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        engine::unicode::Unicode, random::random::Random, state::directions::Directions,
+        state::snake_coords::SnakeCoords,
+    };
+
+    use super::*;
+
+    fn place_food(seed: u64) -> Coords {
+        let mut state = GameState::level_1(Coords::new(40, 20));
+        state.rng = Random::seeded(seed);
+
+        match Food::place(&mut state).unwrap() {
+            Placement::Placed(item) => item.coords,
+            Placement::BoardFull => panic!("the arena is empty"),
+        }
+    }
+
+    #[test]
+    fn place_is_decided_by_the_seed() {
+        assert_eq!(place_food(42), place_food(42));
+        assert_ne!(place_food(42), place_food(7));
+    }
+
+    #[test]
+    fn empty_cells_skip_snake_walls_and_food() {
+        let mut state = GameState::level_1(Coords::new(12, 14));
+        state.snake.positions = vec![SnakeCoords::new(2, 4, Directions::Right, true)];
+        state
+            .food
+            .items
+            .push(FoodItem::new(Coords::new(3, 5), FoodKind::Berry));
+        state
+            .arena
             .positions
+            .push((Coords::new(4, 4), Unicode::BoxLightVertical));
+        state.sync_board();
+
+        assert_eq!(
+            state.board.empty_cells(),
+            vec![Coords::new(3, 4), Coords::new(2, 5), Coords::new(4, 5)]
+        );
+    }
+
+    #[test]
+    fn place_only_uses_empty_cells() {
+        let mut state = GameState::level_1(Coords::new(12, 14));
+        state.snake.positions = vec![
+            SnakeCoords::new(2, 4, Directions::Right, true),
+            SnakeCoords::new(3, 4, Directions::Right, true),
+            SnakeCoords::new(4, 4, Directions::Right, true),
+            SnakeCoords::new(2, 5, Directions::Right, true),
+            SnakeCoords::new(3, 5, Directions::Right, true),
+        ];
+        state.sync_board();
+
+        for _ in 0..20 {
+            match Food::place(&mut state) {
+                Ok(Placement::Placed(item)) => assert_eq!(item.coords, Coords::new(4, 5)),
+                other => panic!("expected food at 4,5, got {:?}", other),
+            }
+
+            let _ = Food::remove(&mut state, 0);
+        }
+    }
+
+    #[test]
+    fn fill_tops_up_to_the_count() {
+        let mut state = GameState::level_1(Coords::new(12, 14));
+        state.food = Food::new(4);
+
+        assert!(matches!(Food::fill(&mut state), Ok(Placement::Placed(_))));
+        assert_eq!(state.food.items.len(), 4);
+
+        let _ = Food::remove(&mut state, 1);
+        let _ = Food::fill(&mut state).unwrap();
+        let mut cells: Vec<(i16, i16)> = state
+            .food
+            .items
             .iter()
-            .any(|&pos| (pos.coords.x == rand_cols && pos.coords.y == rand_rows))
-        {
-            return Food::new_random(state, count);
+            .map(|f| (f.coords.x, f.coords.y))
+            .collect();
+        cells.sort();
+        cells.dedup();
+
+        assert_eq!(cells.len(), 4);
+    }
+
+    #[test]
+    fn fill_leaves_what_room_there_is() {
+        let mut state = GameState::level_1(Coords::new(12, 14));
+        state.food = Food::new(10);
+
+        let _ = Food::fill(&mut state).unwrap();
+
+        assert_eq!(state.food.items.len(), 6);
+        assert!(state.board.empty_cells().is_empty());
+    }
+
+    #[test]
+    fn fill_ignores_the_bonus() {
+        let mut state = GameState::level_1(Coords::new(12, 14));
+        state.food = Food::new(2);
+        let _ = Food::place_kind(&mut state, FoodKind::Golden).unwrap();
+
+        let _ = Food::fill(&mut state).unwrap();
+
+        assert_eq!(state.food.items.len(), 3);
+    }
+
+    #[test]
+    fn bonus_counts_down_and_expires() {
+        let mut state = GameState::level_1(Coords::new(12, 14));
+        state.food.items = vec![
+            FoodItem::new(Coords::new(2, 4), FoodKind::Golden),
+            FoodItem::new(Coords::new(3, 4), FoodKind::Apple),
+        ];
+        state.food.items[0].ticks_left = Some(BONUS_WARNING + 1);
+        state.sync_board();
+
+        assert_eq!(state.food.items[0].glyph(), Unicode::BlackStar);
+        assert!(Food::tick(&mut state).unwrap().is_empty());
+        assert_eq!(state.food.items[0].ticks_left, Some(BONUS_WARNING));
+        assert_eq!(state.food.items[0].glyph(), Unicode::WhiteStar);
+
+        let mut expired = Vec::new();
+        for _ in 0..BONUS_WARNING {
+            expired.extend(Food::tick(&mut state).unwrap());
+        }
+
+        assert_eq!(expired.len(), 1);
+        assert_eq!(expired[0].coords, Coords::new(2, 4));
+        assert_eq!(state.food.at(Coords::new(2, 4)), None);
+        assert_eq!(state.food.at(Coords::new(3, 4)), Some(0));
+    }
+
+    #[test]
+    fn bonus_turns_up_eventually() {
+        let mut state = GameState::level_1(Coords::new(12, 14));
+
+        let seen = (0..1000).any(|_| {
+            let _ = Food::tick(&mut state).unwrap();
+            state.food.bonus().is_some()
+        });
+
+        assert!(seen);
+        assert!(state.food.items.len() <= 1);
+    }
+
+    #[test]
+    fn kinds_round_trip_by_name() {
+        for kind in FoodKind::ALL {
+            assert_eq!(FoodKind::from_name(kind.name()), Some(kind));
         }
 
-        state.food.positions[0].x = rand_cols;
-        state.food.positions[0].y = rand_rows;
+        assert_eq!(FoodKind::from_name("pear"), None);
+    }
+
+    #[test]
+    fn place_on_a_full_board() {
+        let mut state = GameState::level_1(Coords::new(12, 14));
+        state.snake.positions = (2..=4)
+            .flat_map(|x| (4..=5).map(move |y| SnakeCoords::new(x, y, Directions::Right, true)))
+            .collect();
+        state.sync_board();
 
-        Ok(state)
+        assert_eq!(Food::place(&mut state), Ok(Placement::BoardFull));
+        assert_eq!(Food::fill(&mut state), Ok(Placement::BoardFull));
+        assert!(state.food.items.is_empty());
     }
 }
@@ -1,30 +1,257 @@
-use crate::engine::coords::Coords;
+use std::time::Duration;
+
+use crate::{engine::coords::Coords, error::SnakeError, random::random::Random};
 
 use super::arena::Arena;
-use super::food::Food;
+use super::board::{Board, Cell};
+use super::controls::{Action, Controls};
+use super::difficulty::Difficulty;
+use super::directions::Directions;
+use super::food::{Food, Placement};
+use super::goal::Goal;
+use super::mode::Mode;
 use super::snake::Snake;
+#[cfg(test)]
+use super::snake_coords::SnakeCoords;
+use super::tick::{Death, TickEvent, TickOutcome};
 
 #[derive(Debug)]
 pub struct GameState {
     pub snake: Snake,
     pub food: Food,
     pub arena: Arena,
+    pub board: Board, // what's in each cell of the arena
     pub score: i16,
-    pub c_dimensions: Coords, // console dimensions
+    pub difficulty: Difficulty,
+    pub mode: Mode,
+    pub quit: bool,              // the player ended the game themselves
+    pub won: bool,               // snake filled the arena or reached the goal
+    pub level: Option<String>,   // the name of the level being played, if not classic's
+    pub spawn: Option<Coords>,   // where snake starts, otherwise the middle of the arena
+    pub goal: Option<Goal>,      // what completes the level
+    pub start_score: i16,        // the score the level started with
+    pub elapsed: Duration,       // time spent playing, not counting pauses
+    pub campaign: Option<usize>, // which campaign level this is, if it is one
+    pub rng: Random,             // every random choice in the game comes from here
+    pub c_dimensions: Coords,    // console dimensions
 }
 
 impl GameState {
     pub fn new() -> Self {
         GameState {
             snake: Snake::new(),
-            food: Food {
-                positions: [Coords::new(-1, -1); 3],
-            },
+            food: Food::new(Mode::default().food_count()),
             arena: Arena::new(),
+            board: Board::default(),
             score: 0,
+            difficulty: Difficulty::default(),
+            mode: Mode::default(),
+            quit: false,
+            won: false,
+            level: None,
+            spawn: None,
+            goal: None,
+            start_score: 0,
+            elapsed: Duration::ZERO,
+            campaign: None,
+            rng: Random::time_seed(),
             c_dimensions: Coords::new(0, 0),
         }
     }
+
+    //
+    // How long a tick should currently last, given the difficulty and how far
+    // into the game we are.
+    //
+    pub fn tick_interval(&self) -> Duration {
+        self.difficulty
+            .speed_curve()
+            .tick_interval(self.score.max(0) as u32, self.snake.positions.len() as u32)
+    }
+
+    /// Plays one tick of the game, given what the player did during it, and
+    /// reports what happened. Nothing is drawn here, the screen is drawn
+    /// from the state afterwards, so the game can be run without one.
+    pub fn tick(&mut self, action: Option<Action>) -> Result<TickOutcome, SnakeError> {
+        let mut outcome = TickOutcome::default();
+
+        // Boosting takes an extra step this tick.
+        let steps = match action {
+            Some(Action::Turn(direction)) => {
+                Controls::turn(&mut self.snake, direction);
+                1
+            }
+            Some(Action::Boost) => 2,
+            Some(Action::Quit) => {
+                self.quit = true;
+                return Ok(outcome);
+            }
+            _ => 1,
+        };
+
+        // Time only counts once snake has set off, not while he's waiting
+        // at the start.
+        if self.snake.next_direction() != Directions::None {
+            self.elapsed += self.tick_interval();
+        }
+
+        for _ in 0..steps {
+            if !self.snake.x_x && !self.won {
+                self.step(&mut outcome)?;
+            }
+        }
+
+        Ok(outcome)
+    }
+
+    //
+    // Puts snake where the level starts him, or else in the middle of the
+    // arena.
+    //
+    pub fn place_snake(&mut self) -> Result<Coords, SnakeError> {
+        let coords = match self.spawn {
+            Some(spawn) => spawn,
+            None => {
+                let (x_middle, y_middle) = Arena::middle_coords(&self.arena)?;
+                Coords::new(x_middle as i16, y_middle as i16)
+            }
+        };
+
+        self.board.remove_snake(self.snake.positions[0].coords);
+        self.board.add_snake(coords);
+        self.snake.positions[0].coords = coords;
+
+        Ok(coords)
+    }
+
+    //
+    // Moves snake one step and works out what he ran into: food, walls or
+    // himself. Until he sets off he's just put at the start instead.
+    //
+    fn step(&mut self, outcome: &mut TickOutcome) -> Result<(), SnakeError> {
+        /*
+         * If the direction of the snake (including any turn he's about to take) is
+         * something other than None, it should be moving, so mark the head block
+         * as active!
+         */
+        if self.snake.next_direction() != Directions::None {
+            self.snake.positions[0].active = true;
+        }
+
+        if !self.snake.positions[0].active {
+            outcome.push(TickEvent::Placed(self.place_snake()?));
+            return Ok(());
+        }
+
+        // Stepping leaves the tail's cell and moves into a new one for the
+        // head, while the rest of snake stays on the same cells.
+        let tail = self.snake.positions[self.snake.positions.len() - 1].coords;
+        self.snake.step();
+        self.board.remove_snake(tail);
+
+        let head = self.snake.positions[0].coords;
+        let cell = self.board.at(head);
+        self.board.add_snake(head);
+        outcome.push(TickEvent::Moved(head));
+
+        let death = match cell {
+            Cell::Wall => Some(Death::Wall),
+            Cell::Obstacle => Some(Death::Obstacle),
+            Cell::Snake => Some(Death::Himself),
+            _ => None,
+        };
+
+        if let Some(death) = death {
+            self.snake.x_x = true;
+            outcome.push(TickEvent::Died(death));
+        }
+
+        // Did we eat something? Only the head can.
+        if let Some(i) = self.food.at(head).filter(|_| cell == Cell::Food) {
+            let eaten = Food::remove(self, i);
+            self.score += eaten.kind.points();
+            outcome.push(TickEvent::Ate(eaten));
+
+            let length = self.snake.positions.len();
+            match eaten.kind.growth() {
+                g if g > 0 => {
+                    self.snake.grow(g as usize);
+                    for p in &self.snake.positions[length..] {
+                        self.board.add_snake(p.coords);
+                    }
+                    outcome.push(TickEvent::Grew(g as usize));
+                }
+                g => {
+                    let before = self.snake.positions.clone();
+                    self.snake.shrink(g.unsigned_abs() as usize);
+                    for p in &before[self.snake.positions.len()..] {
+                        self.board.remove_snake(p.coords);
+                    }
+                    outcome.push(TickEvent::Shrank(length - self.snake.positions.len()));
+                }
+            }
+
+            // There's nowhere left to put food and nothing left to eat, so
+            // snake has won.
+            if Food::fill(self)? == Placement::BoardFull {
+                self.won = true;
+            }
+        }
+
+        for expired in Food::tick(self)? {
+            outcome.push(TickEvent::Expired(expired));
+        }
+
+        if !self.snake.x_x && self.goal.is_some_and(|g| g.reached(self)) {
+            self.won = true;
+        }
+
+        if self.won {
+            outcome.push(TickEvent::Won);
+        }
+
+        Ok(())
+    }
+
+    //
+    // Moves the arena, snake and food by the given offset, e.g. to recenter
+    // the arena after the terminal has been resized. Inactive coordinates
+    // are left alone.
+    //
+    pub fn translate(&mut self, dx: i16, dy: i16) {
+        let shift = |c: &mut Coords| {
+            if c.is_active() {
+                c.x += dx;
+                c.y += dy;
+            }
+        };
+
+        for (coords, _) in self.arena.positions.iter_mut() {
+            shift(coords);
+        }
+
+        for p in self.snake.positions.iter_mut() {
+            shift(&mut p.coords);
+        }
+
+        for f in self.food.items.iter_mut() {
+            shift(&mut f.coords);
+        }
+
+        if let Some(spawn) = self.spawn.as_mut() {
+            shift(spawn);
+        }
+
+        self.board.translate(dx, dy);
+    }
+
+    //
+    // Fills in the board again from the arena, snake and food, for when
+    // they've been set up rather than moved around, e.g. a new level.
+    //
+    pub fn sync_board(&mut self) {
+        self.board = Board::build(&self.arena, &self.snake, &self.food);
+    }
 }
 
 impl Default for GameState {
@@ -32,3 +259,212 @@ impl Default for GameState {
         Self::new()
     }
 }
+
+#[cfg(test)]
+impl GameState {
+    //
+    // A level 1 arena for a console of the given size, with the random
+    // choices seeded. A 12x14 console leaves a 3x2 space inside the walls.
+    //
+    pub(crate) fn level_1(c_dimensions: Coords) -> GameState {
+        let mut state = GameState::new();
+        state.c_dimensions = c_dimensions;
+        state.rng = Random::seeded(1);
+
+        Arena::create_level_1(state)
+    }
+
+    //
+    // A level 1 arena for a 20x14 console with a three block snake turning
+    // from Right to Down and no food. Tests add their own food and turns.
+    //
+    pub(crate) fn turning_snake() -> GameState {
+        let mut state = GameState::level_1(Coords::new(20, 14));
+
+        state.snake.direction = Directions::Down;
+        state.snake.positions = vec![
+            SnakeCoords::new(5, 5, Directions::Down, true),
+            SnakeCoords::new(5, 4, Directions::Right, true),
+            SnakeCoords::new(4, 4, Directions::Right, true),
+        ];
+        state.sync_board();
+
+        state
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::{
+        engine::unicode::Unicode,
+        state::food::{FoodItem, FoodKind},
+    };
+
+    use super::*;
+
+    //
+    // Puts a piece of food of the given kind just right of snake's head and
+    // turns him to eat it.
+    //
+    fn eat(kind: FoodKind) -> (GameState, TickOutcome) {
+        let mut state = GameState::turning_snake();
+        let food = FoodItem::new(Coords::new(6, 5), kind);
+        state.food.items = vec![food];
+        state.sync_board();
+
+        let outcome = state.tick(Some(Action::Turn(Directions::Right))).unwrap();
+
+        (state, outcome)
+    }
+
+    #[test]
+    fn tick_steps_and_drops_tail() {
+        let mut state = GameState::turning_snake();
+
+        let outcome = state.tick(Some(Action::Turn(Directions::Left))).unwrap();
+
+        assert_eq!(outcome.events, vec![TickEvent::Moved(Coords::new(4, 5))]);
+        assert_eq!(state.board.at(Coords::new(4, 4)), Cell::Empty);
+        assert_eq!(state.board.at(Coords::new(4, 5)), Cell::Snake);
+        assert_eq!(state.elapsed, state.tick_interval());
+    }
+
+    #[test]
+    fn tick_eats_by_kind() {
+        let (state, outcome) = eat(FoodKind::Golden);
+        assert_eq!(outcome.died(), None);
+        assert!(outcome.events.contains(&TickEvent::Grew(3)));
+        assert_eq!(state.score, 5);
+        assert_eq!(state.snake.positions.len(), 6);
+
+        let (state, outcome) = eat(FoodKind::Berry);
+        assert!(outcome.events.contains(&TickEvent::Shrank(2)));
+        assert_eq!(state.score, 2);
+        assert_eq!(state.snake.positions.len(), 1);
+        assert_eq!(state.snake.positions[0].coords, Coords::new(6, 5));
+        assert_ne!(state.board.at(Coords::new(5, 5)), Cell::Snake);
+    }
+
+    #[test]
+    fn tick_refills_the_food() {
+        let (state, outcome) = eat(FoodKind::Apple);
+
+        assert_eq!(
+            outcome.events[..2],
+            [
+                TickEvent::Moved(Coords::new(6, 5)),
+                TickEvent::Ate(FoodItem::new(Coords::new(6, 5), FoodKind::Apple))
+            ]
+        );
+        assert_eq!(state.food.items.len(), state.food.count);
+        assert_eq!(state.food.at(Coords::new(6, 5)), None);
+        assert!(!state.won);
+    }
+
+    #[test]
+    fn tick_hits_walls_and_himself() {
+        let mut state = GameState::turning_snake();
+        let outcome = state.tick(None).unwrap();
+        assert_eq!(outcome.died(), Some(Death::Wall));
+        assert!(state.snake.x_x);
+
+        // Dead snakes don't move any more.
+        assert_eq!(state.tick(None).unwrap(), TickOutcome::default());
+
+        let mut state = GameState::turning_snake();
+        state
+            .arena
+            .positions
+            .push((Coords::new(6, 5), Unicode::BlackSquare));
+        state.sync_board();
+        let outcome = state.tick(Some(Action::Turn(Directions::Right))).unwrap();
+        assert_eq!(outcome.died(), Some(Death::Obstacle));
+
+        // Turning back on himself runs into the block behind his head.
+        let mut state = GameState::turning_snake();
+        state.snake.direction = Directions::Up;
+        let outcome = state.tick(None).unwrap();
+        assert_eq!(outcome.died(), Some(Death::Himself));
+    }
+
+    #[test]
+    fn tick_wins_at_the_goal() {
+        let mut state = GameState::turning_snake();
+        state.score = 8;
+        state.goal = Some(Goal::Score(10));
+        state.food.items = vec![FoodItem::new(Coords::new(6, 5), FoodKind::Berry)];
+        state.sync_board();
+
+        let outcome = state.tick(Some(Action::Turn(Directions::Right))).unwrap();
+
+        assert_eq!(state.score, 10);
+        assert!(outcome.won());
+        assert!(state.won);
+    }
+
+    #[test]
+    fn tick_survives_by_the_clock() {
+        let mut state = GameState::turning_snake();
+        state.snake.direction = Directions::Left;
+        state.goal = Some(Goal::Survive(1));
+        state.elapsed = Duration::from_secs(1) - state.tick_interval();
+
+        assert!(state.tick(None).unwrap().won());
+    }
+
+    #[test]
+    fn tick_waits_at_the_spawn() {
+        let mut state = GameState::turning_snake();
+        state.snake = Snake::new();
+        state.spawn = Some(Coords::new(3, 5));
+        state.goal = Some(Goal::Survive(1));
+        state.sync_board();
+
+        for _ in 0..100 {
+            assert!(!state.tick(None).unwrap().won());
+        }
+        assert_eq!(state.elapsed, Duration::ZERO);
+
+        let outcome = state.tick(Some(Action::Turn(Directions::Right))).unwrap();
+
+        assert!(!outcome.won());
+        assert_eq!(state.elapsed, state.tick_interval());
+    }
+
+    #[test]
+    fn tick_boosts_and_quits() {
+        let mut state = GameState::turning_snake();
+        state.snake.direction = Directions::Left;
+
+        let outcome = state.tick(Some(Action::Boost)).unwrap();
+        assert_eq!(
+            outcome.events,
+            vec![
+                TickEvent::Moved(Coords::new(4, 5)),
+                TickEvent::Moved(Coords::new(3, 5))
+            ]
+        );
+
+        assert_eq!(
+            state.tick(Some(Action::Quit)).unwrap(),
+            TickOutcome::default()
+        );
+        assert!(state.quit);
+        assert_eq!(state.snake.positions[0].coords, Coords::new(3, 5));
+    }
+
+    #[test]
+    fn tick_starts_at_the_spawn() {
+        let mut state = GameState::turning_snake();
+        state.snake = Snake::new();
+        state.spawn = Some(Coords::new(3, 5));
+        state.sync_board();
+
+        let outcome = state.tick(None).unwrap();
+
+        assert_eq!(outcome.events, vec![TickEvent::Placed(Coords::new(3, 5))]);
+        assert_eq!(state.board.at(Coords::new(3, 5)), Cell::Snake);
+    }
+}
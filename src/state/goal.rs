@@ -0,0 +1,123 @@
+use std::{fmt, str::FromStr};
+
+use crate::error::SnakeError;
+
+use super::gamestate::GameState;
+
+/// What has to be done to complete a level.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Goal {
+    /// Score this many points during the level.
+    Score(i16),
+    /// Grow snake to this many blocks long.
+    Length(usize),
+    /// Stay alive for this many seconds.
+    Survive(u64),
+}
+
+impl Goal {
+    pub fn reached(&self, state: &GameState) -> bool {
+        match *self {
+            Goal::Score(points) => state.score - state.start_score >= points,
+            Goal::Length(blocks) => state.snake.positions.len() >= blocks,
+            Goal::Survive(seconds) => state.elapsed.as_secs() >= seconds,
+        }
+    }
+
+    //
+    // What to do, as told to the player, e.g. `Score 20 points`.
+    //
+    pub fn describe(&self) -> String {
+        match *self {
+            Goal::Score(points) => format!("Score {} points", points),
+            Goal::Length(blocks) => format!("Grow {} blocks long", blocks),
+            Goal::Survive(seconds) => format!("Survive for {} seconds", seconds),
+        }
+    }
+
+    //
+    // How far along the goal is, e.g. `3/20 points`.
+    //
+    pub fn progress(&self, state: &GameState) -> String {
+        match *self {
+            Goal::Score(points) => format!("{}/{} points", state.score - state.start_score, points),
+            Goal::Length(blocks) => format!("{}/{} long", state.snake.positions.len(), blocks),
+            Goal::Survive(seconds) => format!("{}/{}s", state.elapsed.as_secs(), seconds),
+        }
+    }
+}
+
+impl fmt::Display for Goal {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Goal::Score(points) => write!(f, "score {}", points),
+            Goal::Length(blocks) => write!(f, "length {}", blocks),
+            Goal::Survive(seconds) => write!(f, "survive {}", seconds),
+        }
+    }
+}
+
+impl FromStr for Goal {
+    type Err = SnakeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let goal = match s.split_whitespace().collect::<Vec<&str>>().as_slice() {
+            ["score", n] => n.parse().ok().filter(|&n| n > 0).map(Goal::Score),
+            ["length", n] => n.parse().ok().filter(|&n| n > 1).map(Goal::Length),
+            ["survive", n] => n.parse().ok().filter(|&n| n > 0).map(Goal::Survive),
+            _ => None,
+        };
+
+        goal.ok_or_else(|| {
+            SnakeError::new(&format!(
+                "'{}' is not a goal like score 20, length 15 or survive 60",
+                s
+            ))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::state::{directions::Directions, snake_coords::SnakeCoords};
+
+    use super::*;
+
+    #[test]
+    fn goals_round_trip_as_text() {
+        for goal in [Goal::Score(20), Goal::Length(15), Goal::Survive(60)] {
+            assert_eq!(goal.to_string().parse::<Goal>(), Ok(goal));
+        }
+
+        assert!("score -3".parse::<Goal>().is_err());
+        assert!("length 1".parse::<Goal>().is_err());
+        assert!("fly 10".parse::<Goal>().is_err());
+    }
+
+    #[test]
+    fn score_counts_from_the_start_of_the_level() {
+        let mut state = GameState::new();
+        state.start_score = 30;
+        state.score = 38;
+
+        assert!(!Goal::Score(10).reached(&state));
+        assert_eq!(Goal::Score(10).progress(&state), "8/10 points");
+
+        state.score = 40;
+        assert!(Goal::Score(10).reached(&state));
+    }
+
+    #[test]
+    fn length_and_survive() {
+        let mut state = GameState::new();
+        state.snake.positions = vec![SnakeCoords::new(5, 5, Directions::Up, true); 4];
+        state.elapsed = Duration::from_millis(59_900);
+
+        assert!(Goal::Length(4).reached(&state));
+        assert!(!Goal::Length(5).reached(&state));
+        assert!(!Goal::Survive(60).reached(&state));
+        assert_eq!(Goal::Survive(60).progress(&state), "59/60s");
+    }
+}
@@ -0,0 +1,347 @@
+use std::collections::HashMap;
+
+use crate::{engine::storage::DataFile, error::SnakeError};
+
+use super::{arena::Arena, difficulty::Difficulty, gamestate::GameState, mode::Mode};
+
+// How many scores are kept on each board.
+pub const MAX_ENTRIES: usize = 10;
+
+// The longest name a player can put against a score.
+pub const MAX_NAME_LEN: usize = 12;
+
+// Kept in `~/.local/share/terminal_snake/scores`.
+const FILE: DataFile = DataFile::new("scores", "high scores", 1);
+
+/// Scores are only compared against games played the same way, so each
+/// combination of difficulty, mode and arena size has its own table.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Board {
+    pub difficulty: Difficulty,
+    pub mode: Mode,
+    pub width: u16,
+    pub height: u16,
+}
+
+impl Board {
+    pub fn for_game(state: &GameState) -> Result<Board, SnakeError> {
+        let (width, height) = Arena::size(&state.arena)?;
+
+        Ok(Board {
+            difficulty: state.difficulty,
+            mode: state.mode,
+            width,
+            height,
+        })
+    }
+
+    /// e.g. `normal classic 40x12`, as used in the scores file.
+    pub fn name(&self) -> String {
+        format!(
+            "{} {} {}x{}",
+            self.difficulty.name(),
+            self.mode.name(),
+            self.width,
+            self.height
+        )
+    }
+
+    pub fn from_name(name: &str) -> Option<Board> {
+        let mut parts = name.split_whitespace();
+        let difficulty = Difficulty::from_name(parts.next()?)?;
+        let mode = Mode::from_name(parts.next()?)?;
+        let (width, height) = parts.next()?.split_once('x')?;
+
+        if parts.next().is_some() {
+            return None;
+        }
+
+        Some(Board {
+            difficulty,
+            mode,
+            width: width.parse().ok()?,
+            height: height.parse().ok()?,
+        })
+    }
+
+    // Orders boards by difficulty, then mode, then size.
+    fn sort_key(&self) -> (usize, &'static str, u16, u16) {
+        let difficulty = Difficulty::ALL
+            .iter()
+            .position(|d| *d == self.difficulty)
+            .unwrap_or(0);
+
+        (difficulty, self.mode.name(), self.width, self.height)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entry {
+    pub name: String,
+    pub score: i16,
+}
+
+/// The best scores for every board played so far, best first.
+#[derive(Debug, Default, PartialEq)]
+pub struct HighScores {
+    tables: HashMap<Board, Vec<Entry>>,
+}
+
+impl HighScores {
+    pub fn new() -> Self {
+        HighScores::default()
+    }
+
+    /// Loads the scores file, or an empty table if there isn't one yet.
+    pub fn load() -> Result<HighScores, SnakeError> {
+        Ok(FILE.load(HighScores::parse)?.unwrap_or_default())
+    }
+
+    pub fn save(&self) -> Result<(), SnakeError> {
+        Ok(FILE.save(&self.to_text())?)
+    }
+
+    /// Parses a scores file. After the version line, each board has a
+    /// `[board]` heading followed by one `score name` line per entry, best
+    /// first:
+    ///
+    /// ```text
+    /// version 1
+    ///
+    /// [normal classic 40x12]
+    /// 42 sam
+    /// 17 alex
+    /// ```
+    pub fn parse(text: &str) -> Result<HighScores, SnakeError> {
+        let mut scores = HighScores::new();
+        let mut board = None;
+
+        for (line_no, line) in FILE.lines(text)? {
+            let error =
+                |message: String| SnakeError::new(&format!("line {}: {}", line_no, message));
+
+            if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+                let found = Board::from_name(name)
+                    .ok_or_else(|| error(format!("unknown board '{}'", name)))?;
+
+                board = Some(found);
+                scores.tables.entry(found).or_default();
+                continue;
+            }
+
+            let board = board.ok_or_else(|| error(String::from("score before any [board]")))?;
+
+            let (score, name) = line
+                .split_once(' ')
+                .ok_or_else(|| error(format!("expected `score name`, found '{}'", line)))?;
+            let score = score
+                .parse()
+                .map_err(|_| error(format!("'{}' is not a score", score)))?;
+
+            if name.chars().count() > MAX_NAME_LEN {
+                return Err(error(format!(
+                    "'{}' is longer than {} characters",
+                    name, MAX_NAME_LEN
+                )));
+            }
+
+            let entries = scores.tables.entry(board).or_default();
+
+            if entries.len() >= MAX_ENTRIES {
+                return Err(error(format!(
+                    "more than {} scores for [{}]",
+                    MAX_ENTRIES,
+                    board.name()
+                )));
+            }
+
+            if entries.last().is_some_and(|e| e.score < score) {
+                return Err(error(format!(
+                    "{} is higher than the score above it",
+                    score
+                )));
+            }
+
+            entries.push(Entry {
+                name: name.to_string(),
+                score,
+            });
+        }
+
+        Ok(scores)
+    }
+
+    pub fn to_text(&self) -> String {
+        let mut boards = self.boards();
+        boards.retain(|b| !self.entries(b).is_empty());
+
+        let mut text = format!(
+            "# {} high scores\nversion {}\n",
+            env!("CARGO_PKG_NAME"),
+            FILE.version()
+        );
+
+        for board in boards {
+            text.push_str(&format!("\n[{}]\n", board.name()));
+
+            for entry in self.entries(&board) {
+                text.push_str(&format!("{} {}\n", entry.score, entry.name));
+            }
+        }
+
+        text
+    }
+
+    /// Every board with a table, in a stable order.
+    pub fn boards(&self) -> Vec<Board> {
+        let mut boards: Vec<Board> = self.tables.keys().copied().collect();
+        boards.sort_by_key(|b| b.sort_key());
+
+        boards
+    }
+
+    pub fn entries(&self, board: &Board) -> &[Entry] {
+        self.tables.get(board).map_or(&[], |e| e.as_slice())
+    }
+
+    /// Whether the score would make it onto the board.
+    pub fn qualifies(&self, board: &Board, score: i16) -> bool {
+        let entries = self.entries(board);
+
+        score > 0 && (entries.len() < MAX_ENTRIES || entries.iter().any(|e| score > e.score))
+    }
+
+    /// Adds a score, below any equal ones already there, and returns its
+    /// 0-based rank. Returns `None` if it didn't make the board.
+    pub fn insert(&mut self, board: Board, name: &str, score: i16) -> Option<usize> {
+        let name: String = name.trim().chars().take(MAX_NAME_LEN).collect();
+        let name = match name.is_empty() {
+            true => String::from("anonymous"),
+            false => name,
+        };
+
+        let entries = self.tables.entry(board).or_default();
+        let rank = entries.iter().take_while(|e| e.score >= score).count();
+
+        if rank >= MAX_ENTRIES {
+            return None;
+        }
+
+        entries.insert(rank, Entry { name, score });
+        entries.truncate(MAX_ENTRIES);
+
+        Some(rank)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn board() -> Board {
+        Board {
+            difficulty: Difficulty::Normal,
+            mode: Mode::Classic,
+            width: 40,
+            height: 12,
+        }
+    }
+
+    #[test]
+    fn insert_keeps_best_first_and_bounded() {
+        let mut scores = HighScores::new();
+
+        for score in 1..=MAX_ENTRIES as i16 {
+            scores.insert(board(), "a", score);
+        }
+
+        assert_eq!(scores.insert(board(), "b", 5), Some(6));
+        assert_eq!(scores.entries(&board()).len(), MAX_ENTRIES);
+        assert_eq!(scores.entries(&board())[0].score, 10);
+        assert_eq!(scores.entries(&board())[MAX_ENTRIES - 1].score, 2);
+        assert_eq!(scores.insert(board(), "c", 1), None);
+    }
+
+    #[test]
+    fn qualifies_needs_a_positive_score_that_beats_the_board() {
+        let mut scores = HighScores::new();
+        assert!(!scores.qualifies(&board(), 0));
+        assert!(scores.qualifies(&board(), 1));
+
+        for _ in 0..MAX_ENTRIES {
+            scores.insert(board(), "a", 3);
+        }
+
+        assert!(!scores.qualifies(&board(), 3));
+        assert!(scores.qualifies(&board(), 4));
+    }
+
+    #[test]
+    fn insert_tidies_names() {
+        let mut scores = HighScores::new();
+        scores.insert(board(), "  ", 1);
+        scores.insert(board(), "a very long name indeed", 1);
+
+        assert_eq!(scores.entries(&board())[0].name, "anonymous");
+        assert_eq!(scores.entries(&board())[1].name, "a very long ");
+    }
+
+    #[test]
+    fn to_text_and_parse_round_trip() {
+        let mut scores = HighScores::new();
+        scores.insert(board(), "sam", 42);
+        scores.insert(board(), "alex b", 17);
+        scores.insert(
+            Board {
+                difficulty: Difficulty::Easy,
+                ..board()
+            },
+            "kim",
+            3,
+        );
+
+        let text = scores.to_text();
+
+        assert_eq!(
+            text,
+            "# terminal_snake high scores\nversion 1\n\n[easy classic 40x12]\n3 kim\n\n[normal classic 40x12]\n42 sam\n17 alex b\n"
+        );
+        assert_eq!(HighScores::parse(&text), Ok(scores));
+    }
+
+    #[test]
+    fn parse_errors_point_at_the_line() {
+        let check = |text: &str, message: &str| {
+            assert_eq!(HighScores::parse(text), Err(SnakeError::new(message)));
+        };
+
+        check(
+            "[normal classic 40x12]",
+            "line 1: expected `version N`, found '[normal classic 40x12]'",
+        );
+        check(
+            "version 9",
+            "line 1: this file is version 9 but only version 1 can be loaded",
+        );
+        check("version 1\n42 sam", "line 2: score before any [board]");
+        check(
+            "version 1\n[normal classic 4x]",
+            "line 2: unknown board 'normal classic 4x'",
+        );
+        check(
+            "version 1\n[hard classic 4x4]\nlots sam",
+            "line 3: 'lots' is not a score",
+        );
+        check(
+            "version 1\n[hard classic 4x4]\n5 a very long name",
+            "line 3: 'a very long name' is longer than 12 characters",
+        );
+        check(
+            "version 1\n[hard classic 4x4]\n5 sam\n6 alex",
+            "line 4: 6 is higher than the score above it",
+        );
+
+        let full = format!("version 1\n[hard classic 4x4]\n{}", "5 sam\n".repeat(11));
+        check(&full, "line 13: more than 10 scores for [hard classic 4x4]");
+    }
+}
@@ -0,0 +1,444 @@
+use std::path::Path;
+
+use crate::{
+    engine::{coords::Coords, storage::Storage},
+    error::SnakeError,
+};
+
+use super::{arena::Arena, directions::Directions, gamestate::GameState, goal::Goal, mode::Mode};
+
+// The smallest map with a cell inside its walls.
+const MIN_SIZE: u16 = 3;
+
+/// An arena read from a level file, along with the rules it's played by.
+///
+/// Positions are counted from the map's top left corner, starting at 0.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Level {
+    pub name: String,
+    pub width: u16,
+    pub height: u16,
+    pub spawn: Coords,
+    /// Which way snake sets off, or `None` to wait for the player.
+    pub direction: Directions,
+    /// How many pieces of food are kept out at once.
+    pub food: usize,
+    /// Whether timed bonuses turn up.
+    pub bonus: bool,
+    /// What completes the level, if anything does.
+    pub goal: Option<Goal>,
+    /// Whether the score so far is kept going into the level, in a campaign.
+    pub carry: bool,
+    pub walls: Vec<Coords>,
+}
+
+impl Level {
+    /// Reads and checks a level file, putting the file's name in front of
+    /// any error.
+    pub fn load(path: &Path) -> Result<Level, SnakeError> {
+        match Storage::read(path)? {
+            Some(text) => Level::parse(&text)
+                .map_err(|e| SnakeError::new(&format!("{}: {}", path.display(), e))),
+            None => Err(SnakeError::new(&format!(
+                "{}: there's no level file here",
+                path.display()
+            ))),
+        }
+    }
+
+    /// Parses a level: a header of `key value` lines, then `map` and the
+    /// arena drawn out with `#` for walls and `.` or spaces for open cells.
+    /// Walls can go anywhere, as long as the edge is closed, and are joined
+    /// up with box drawing pieces when the level is played.
+    /// Blank lines and lines starting with `#` in the header are ignored.
+    ///
+    /// ```text
+    /// name Garden
+    /// size 12x6
+    /// spawn 5,2
+    /// direction right
+    /// food 2
+    /// bonus off
+    /// goal score 20
+    /// score reset
+    /// map
+    /// ############
+    /// #..........#
+    /// #..........#
+    /// #..........#
+    /// #..........#
+    /// ############
+    /// ```
+    ///
+    /// `name`, `size` and `spawn` are needed, the rest default to waiting
+    /// for the player, classic's food, bonuses on, no goal and carrying the
+    /// score over. Goals are `score N` points, `length N` blocks or
+    /// `survive N` seconds.
+    pub fn parse(text: &str) -> Result<Level, SnakeError> {
+        let error = |line_no: usize, column: usize, message: String| {
+            SnakeError::new(&format!("line {}, column {}: {}", line_no, column, message))
+        };
+
+        let mut name = None;
+        let mut size = None;
+        let mut spawn = None;
+        let mut direction = None;
+        let mut food = None;
+        let mut bonus = None;
+        let mut goal = None;
+        let mut carry = None;
+
+        let mut lines = text.lines().enumerate().map(|(i, line)| (i + 1, line));
+        let mut map_line = None;
+
+        for (n, line) in lines.by_ref() {
+            let trimmed = line.trim();
+
+            if trimmed.is_empty() || trimmed.starts_with('#') {
+                continue;
+            }
+
+            if trimmed == "map" {
+                map_line = Some(n);
+                break;
+            }
+
+            let (key, value) = trimmed.split_once(' ').unwrap_or((trimmed, ""));
+            let value = value.trim();
+            // Where the value starts, for pointing at it in errors.
+            let column = line.len() - line.trim_start().len() + key.len() + 2;
+
+            let invalid = |what: &str| error(n, column, format!("'{}' is not {}", value, what));
+
+            let slot_taken = match key {
+                "name" if !value.is_empty() => name.replace(value.to_string()).is_some(),
+                "name" => return Err(error(n, column, String::from("the name is empty"))),
+                "size" => {
+                    let parsed = value
+                        .split_once('x')
+                        .and_then(|(w, h)| Some((w.parse::<u16>().ok()?, h.parse::<u16>().ok()?)))
+                        .ok_or_else(|| invalid("a size like 20x10"))?;
+
+                    if parsed.0 < MIN_SIZE || parsed.1 < MIN_SIZE {
+                        return Err(error(
+                            n,
+                            column,
+                            format!("the arena must be at least {}x{}", MIN_SIZE, MIN_SIZE),
+                        ));
+                    }
+
+                    size.replace((n, column, parsed)).is_some()
+                }
+                "spawn" => {
+                    let parsed = value
+                        .split_once(',')
+                        .and_then(|(x, y)| Some((x.parse::<u16>().ok()?, y.parse::<u16>().ok()?)))
+                        .ok_or_else(|| invalid("a position like 10,4"))?;
+
+                    spawn.replace((n, column, parsed)).is_some()
+                }
+                "direction" => {
+                    let parsed =
+                        Directions::from_name(value).ok_or_else(|| invalid("a direction"))?;
+
+                    direction.replace(parsed).is_some()
+                }
+                "food" => match value.parse::<usize>() {
+                    Ok(count) if count > 0 => food.replace(count).is_some(),
+                    _ => return Err(invalid("a number of pieces of food")),
+                },
+                "bonus" => {
+                    let parsed = match value {
+                        "on" => true,
+                        "off" => false,
+                        _ => return Err(invalid("on or off")),
+                    };
+
+                    bonus.replace(parsed).is_some()
+                }
+                "goal" => {
+                    let parsed = value
+                        .parse::<Goal>()
+                        .map_err(|e| error(n, column, e.to_string()))?;
+
+                    goal.replace(parsed).is_some()
+                }
+                "score" => {
+                    let parsed = match value {
+                        "carry" => true,
+                        "reset" => false,
+                        _ => return Err(invalid("carry or reset")),
+                    };
+
+                    carry.replace(parsed).is_some()
+                }
+                _ => {
+                    return Err(error(
+                        n,
+                        column - key.len() - 1,
+                        format!("unknown setting `{}`", key),
+                    ))
+                }
+            };
+
+            if slot_taken {
+                return Err(error(
+                    n,
+                    column - key.len() - 1,
+                    format!("`{}` is set twice", key),
+                ));
+            }
+        }
+
+        let missing = |key: &str| SnakeError::new(&format!("missing `{}`", key));
+
+        let name = name.ok_or_else(|| missing("name"))?;
+        let (size_line, size_column, (width, height)) = size.ok_or_else(|| missing("size"))?;
+        let (spawn_line, spawn_column, (spawn_x, spawn_y)) =
+            spawn.ok_or_else(|| missing("spawn"))?;
+        let map_line = map_line.ok_or_else(|| missing("map"))?;
+
+        let mut rows: Vec<(usize, &str)> = lines.collect();
+
+        // Blank lines after the map are just the end of the file.
+        while rows.last().is_some_and(|(_, row)| row.trim().is_empty()) {
+            rows.pop();
+        }
+
+        if rows.len() != height as usize {
+            return Err(error(
+                size_line,
+                size_column,
+                format!(
+                    "the map should be {} rows high but {} follow `map` on line {}",
+                    height,
+                    rows.len(),
+                    map_line
+                ),
+            ));
+        }
+
+        let mut walls = Vec::new();
+
+        for (y, (n, row)) in rows.into_iter().enumerate() {
+            let cells: Vec<char> = row.chars().collect();
+
+            if cells.len() > width as usize {
+                return Err(error(
+                    n,
+                    width as usize + 1,
+                    format!("the map should be {} columns wide", width),
+                ));
+            }
+
+            for x in 0..width as usize {
+                // Rows can stop early, the rest being open.
+                let c = cells.get(x).copied().unwrap_or(' ');
+                let edge = x == 0 || y == 0 || x == width as usize - 1 || y == height as usize - 1;
+
+                match (c, edge) {
+                    ('#', _) => walls.push(Coords::new(x as i16, y as i16)),
+                    ('.' | ' ', true) => {
+                        return Err(error(
+                            n,
+                            x + 1,
+                            String::from("the edge of the arena needs a wall here"),
+                        ))
+                    }
+                    ('.' | ' ', false) => {}
+                    _ => return Err(error(n, x + 1, format!("unknown map cell '{}'", c))),
+                }
+            }
+        }
+
+        let spawn = Coords::new(spawn_x as i16, spawn_y as i16);
+
+        if spawn_x == 0 || spawn_y == 0 || spawn_x >= width - 1 || spawn_y >= height - 1 {
+            return Err(error(
+                spawn_line,
+                spawn_column,
+                format!("snake has to start inside the {}x{} arena", width, height),
+            ));
+        }
+
+        if walls.contains(&spawn) {
+            return Err(error(
+                spawn_line,
+                spawn_column,
+                String::from("snake can't start inside a wall"),
+            ));
+        }
+
+        Ok(Level {
+            name,
+            width,
+            height,
+            spawn,
+            direction: direction.unwrap_or(Directions::None),
+            food: food.unwrap_or(Mode::default().food_count()),
+            bonus: bonus.unwrap_or(true),
+            goal,
+            carry: carry.unwrap_or(true),
+            walls,
+        })
+    }
+
+    //
+    // Builds the level's arena and rules into the game, with the arena's top
+    // left corner just below the score, ready to be laid out.
+    //
+    pub fn apply(&self, mut state: GameState) -> GameState {
+        let at = |c: &Coords| Coords::new(c.x + Arena::ORIGIN.0, c.y + Arena::ORIGIN.1);
+
+        let walls: Vec<Coords> = self.walls.iter().map(at).collect();
+        state.arena = Arena::from_walls(&walls);
+
+        state.level = Some(self.name.clone());
+        state.spawn = Some(at(&self.spawn));
+        state.goal = self.goal;
+        state.food.count = self.food;
+        state.food.bonus = self.bonus;
+        state.sync_board();
+
+        state
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::state::save::SaveGame;
+
+    use super::*;
+
+    const TEXT: &str = "# A small test level
+name Garden
+size 6x4
+spawn 2,1
+direction right
+food 2
+bonus off
+goal score 20
+score reset
+
+map
+######
+#....#
+#.   #
+######
+";
+
+    fn check(from: &str, to: &str, message: &str) {
+        let text = TEXT.replace(from, to);
+        assert_eq!(Level::parse(&text), Err(SnakeError::new(message)));
+    }
+
+    #[test]
+    fn parse_reads_the_header_and_map() {
+        let level = Level::parse(TEXT).unwrap();
+
+        assert_eq!(level.name, "Garden");
+        assert_eq!((level.width, level.height), (6, 4));
+        assert_eq!(level.spawn, Coords::new(2, 1));
+        assert_eq!(level.direction, Directions::Right);
+        assert_eq!(level.food, 2);
+        assert!(!level.bonus);
+        assert_eq!(level.goal, Some(Goal::Score(20)));
+        assert!(!level.carry);
+        assert_eq!(level.walls.len(), 16);
+    }
+
+    #[test]
+    fn parse_defaults_the_optional_settings() {
+        let text = "name Plain\nsize 3x3\nspawn 1,1\nmap\n###\n#.#\n###\n";
+        let level = Level::parse(text).unwrap();
+
+        assert_eq!(level.direction, Directions::None);
+        assert_eq!(level.food, Mode::default().food_count());
+        assert!(level.bonus);
+        assert_eq!(level.goal, None);
+        assert!(level.carry);
+    }
+
+    #[test]
+    fn parse_errors_point_at_the_header() {
+        check(
+            "size 6x4",
+            "size six",
+            "line 3, column 6: 'six' is not a size like 20x10",
+        );
+        check(
+            "direction right",
+            "direction sideways",
+            "line 5, column 11: 'sideways' is not a direction",
+        );
+        check(
+            "bonus off",
+            "  colour red",
+            "line 7, column 3: unknown setting `colour`",
+        );
+        check(
+            "food 2",
+            "name Again",
+            "line 6, column 1: `name` is set twice",
+        );
+        check(
+            "spawn 2,1",
+            "spawn 5,1",
+            "line 4, column 7: snake has to start inside the 6x4 arena",
+        );
+        check(
+            "goal score 20",
+            "goal score lots",
+            "line 8, column 6: 'score lots' is not a goal like score 20, length 15 or survive 60",
+        );
+        check("spawn 2,1\n", "", "missing `spawn`");
+    }
+
+    #[test]
+    fn parse_errors_point_at_the_map() {
+        check(
+            "#.   #",
+            "#.  x#",
+            "line 14, column 5: unknown map cell 'x'",
+        );
+        check(
+            "#.   #",
+            "#.   ",
+            "line 14, column 6: the edge of the arena needs a wall here",
+        );
+        check(
+            "#....#",
+            "#.#..#",
+            "line 4, column 7: snake can't start inside a wall",
+        );
+        check(
+            "#....#",
+            "#....##",
+            "line 13, column 7: the map should be 6 columns wide",
+        );
+        check(
+            "######\n#....#",
+            "######",
+            "line 3, column 6: the map should be 4 rows high but 3 follow `map` on line 11",
+        );
+    }
+
+    #[test]
+    fn apply_builds_the_arena() {
+        let state = Level::parse(TEXT).unwrap().apply(GameState::new());
+
+        assert_eq!(Arena::size(&state.arena), Ok((6, 4)));
+        assert_eq!(Arena::min_arena_coords(&state.arena), Ok((1, 3)));
+        assert_eq!(state.spawn, Some(Coords::new(3, 4)));
+        assert_eq!(state.goal, Some(Goal::Score(20)));
+        assert_eq!(state.level.as_deref(), Some("Garden"));
+        assert_eq!(state.food.count, 2);
+        assert!(!state.food.bonus);
+
+        let mut state = state;
+        state.c_dimensions = Coords::new(20, 14);
+        assert!(SaveGame::to_text(&state)
+            .unwrap()
+            .ends_with("arena 6x4\n╭────╮\n│    │\n│    │\n╰────╯\n"));
+    }
+}
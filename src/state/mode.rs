@@ -0,0 +1,33 @@
+/// The kind of game being played. High scores are kept separately for each.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Mode {
+    /// Endless play in the procedurally built arena.
+    #[default]
+    Classic,
+    /// The built-in levels, one after another.
+    Campaign,
+}
+
+impl Mode {
+    pub const ALL: [Mode; 2] = [Mode::Classic, Mode::Campaign];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Mode::Classic => "classic",
+            Mode::Campaign => "campaign",
+        }
+    }
+
+    /// How many pieces of food are kept in the arena at once.
+    pub fn food_count(&self) -> usize {
+        match self {
+            Mode::Classic | Mode::Campaign => 3,
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Mode> {
+        let name = name.to_ascii_lowercase();
+
+        Mode::ALL.into_iter().find(|m| m.name() == name)
+    }
+}
@@ -0,0 +1,186 @@
+use std::collections::HashMap;
+
+use crate::{engine::storage::DataFile, error::SnakeError};
+
+// Kept in `~/.local/share/terminal_snake/progress`.
+const FILE: DataFile = DataFile::new("progress", "campaign progress", 1);
+
+/// How far through the campaign the player has got, and the most points
+/// they've scored in each level they've completed.
+#[derive(Debug, PartialEq)]
+pub struct Progress {
+    // How many levels can be played, counting from the first
+    unlocked: usize,
+
+    // Level name to best points
+    best: HashMap<String, i16>,
+}
+
+impl Progress {
+    pub fn new() -> Self {
+        Progress {
+            unlocked: 1,
+            best: HashMap::new(),
+        }
+    }
+
+    /// Loads the progress file, or a fresh start if there isn't one yet.
+    pub fn load() -> Result<Progress, SnakeError> {
+        Ok(FILE.load(Progress::parse)?.unwrap_or_default())
+    }
+
+    pub fn save(&self) -> Result<(), SnakeError> {
+        Ok(FILE.save(&self.to_text())?)
+    }
+
+    /// Parses a progress file. After the version line comes how many levels
+    /// are unlocked, then a `best points name` line for each completed level:
+    ///
+    /// ```text
+    /// version 1
+    /// unlocked 3
+    /// best 12 First steps
+    /// best 20 Pillars
+    /// ```
+    pub fn parse(text: &str) -> Result<Progress, SnakeError> {
+        let mut progress = Progress::new();
+
+        for (line_no, line) in FILE.lines(text)? {
+            let error =
+                |message: String| SnakeError::new(&format!("line {}: {}", line_no, message));
+
+            match line.split_once(' ').unwrap_or((line, "")) {
+                ("unlocked", count) => {
+                    progress.unlocked = count
+                        .trim()
+                        .parse::<usize>()
+                        .map_err(|_| error(format!("'{}' is not a number of levels", count)))?
+                        .max(1);
+                }
+                ("best", rest) => {
+                    let (points, name) = rest.split_once(' ').ok_or_else(|| {
+                        error(format!("expected `best points name`, found '{}'", line))
+                    })?;
+                    let points = points
+                        .parse()
+                        .map_err(|_| error(format!("'{}' is not a score", points)))?;
+
+                    progress.best.insert(name.trim().to_string(), points);
+                }
+                (key, _) => return Err(error(format!("unknown setting `{}`", key))),
+            }
+        }
+
+        Ok(progress)
+    }
+
+    pub fn to_text(&self) -> String {
+        let mut best: Vec<(&String, &i16)> = self.best.iter().collect();
+        best.sort();
+
+        let mut text = format!(
+            "# {} campaign progress\nversion {}\nunlocked {}\n",
+            env!("CARGO_PKG_NAME"),
+            FILE.version(),
+            self.unlocked
+        );
+
+        for (name, points) in best {
+            text.push_str(&format!("best {} {}\n", points, name));
+        }
+
+        text
+    }
+
+    pub fn is_unlocked(&self, index: usize) -> bool {
+        index < self.unlocked
+    }
+
+    pub fn best(&self, name: &str) -> Option<i16> {
+        self.best.get(name).copied()
+    }
+
+    /// Records a level as complete with the points scored in it, unlocking
+    /// the one after. Returns true if it's a new best for the level.
+    pub fn complete(&mut self, index: usize, name: &str, points: i16) -> bool {
+        self.unlocked = self.unlocked.max(index + 2);
+
+        match self.best(name) {
+            Some(best) if best >= points => false,
+            _ => {
+                self.best.insert(name.to_string(), points);
+                true
+            }
+        }
+    }
+}
+
+impl Default for Progress {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_the_first_level_starts_unlocked() {
+        let progress = Progress::new();
+
+        assert!(progress.is_unlocked(0));
+        assert!(!progress.is_unlocked(1));
+    }
+
+    #[test]
+    fn complete_unlocks_the_next_level_and_keeps_the_best() {
+        let mut progress = Progress::new();
+
+        assert!(progress.complete(0, "First steps", 8));
+        assert!(progress.is_unlocked(1));
+        assert!(!progress.is_unlocked(2));
+
+        assert!(!progress.complete(0, "First steps", 5));
+        assert!(progress.complete(0, "First steps", 11));
+        assert_eq!(progress.best("First steps"), Some(11));
+
+        // Replaying an early level doesn't lock later ones again.
+        progress.complete(3, "Maze", 2);
+        progress.complete(0, "First steps", 1);
+        assert!(progress.is_unlocked(4));
+    }
+
+    #[test]
+    fn parse_round_trips() {
+        let mut progress = Progress::new();
+        progress.complete(0, "First steps", 8);
+        progress.complete(1, "Pillars", 20);
+
+        let text = progress.to_text();
+
+        assert_eq!(
+            text,
+            "# terminal_snake campaign progress\nversion 1\nunlocked 3\nbest 8 First steps\nbest 20 Pillars\n"
+        );
+        assert_eq!(Progress::parse(&text), Ok(progress));
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert_eq!(
+            Progress::parse("unlocked 2"),
+            Err(SnakeError::new(
+                "line 1: expected `version N`, found 'unlocked 2'"
+            ))
+        );
+        assert_eq!(
+            Progress::parse("version 1\nbest lots Pillars"),
+            Err(SnakeError::new("line 2: 'lots' is not a score"))
+        );
+        assert_eq!(
+            Progress::parse("version 1\nstars 3"),
+            Err(SnakeError::new("line 2: unknown setting `stars`"))
+        );
+    }
+}
@@ -0,0 +1,542 @@
+use std::time::Duration;
+
+use crate::{
+    engine::{coords::Coords, storage::DataFile, unicode::Unicode},
+    error::SnakeError,
+};
+
+use super::{
+    arena::Arena,
+    difficulty::Difficulty,
+    directions::Directions,
+    food::{FoodItem, FoodKind},
+    gamestate::GameState,
+    mode::Mode,
+    snake_coords::SnakeCoords,
+};
+
+// Kept in `~/.local/share/terminal_snake/save`.
+const FILE: DataFile = DataFile::new("save", "the game", 8);
+
+/// A game put aside to be continued later.
+///
+/// Positions are stored relative to the arena's top left corner, so a save
+/// can be picked up again in a window of a different size. The tick speed
+/// isn't stored, it follows from the difficulty, score and length.
+pub struct SaveGame;
+
+impl SaveGame {
+    pub fn exists() -> bool {
+        FILE.exists()
+    }
+
+    pub fn save(state: &GameState) -> Result<(), SnakeError> {
+        Ok(FILE.save(&SaveGame::to_text(state)?)?)
+    }
+
+    /// Reads the save, or `None` if there isn't one.
+    pub fn load() -> Result<Option<GameState>, SnakeError> {
+        Ok(FILE.load(SaveGame::parse)?)
+    }
+
+    /// Deletes the save, once it's been continued.
+    pub fn remove() -> Result<(), SnakeError> {
+        Ok(FILE.remove()?)
+    }
+
+    /// Writes the game out as lines of `key value`, ending with the arena
+    /// drawn out row by row:
+    ///
+    /// ```text
+    /// version 8
+    /// difficulty normal
+    /// mode campaign
+    /// level Garden
+    /// campaign 2
+    /// goal score 20
+    /// spawn 4,2
+    /// start 12
+    /// elapsed 41250
+    /// bonus on
+    /// rng pcg32 2459750275 2885390081777926815
+    /// score 3
+    /// direction right
+    /// turns down
+    /// snake 4,2,right,1 3,2,right,1
+    /// food 3 6,1,apple 2,2,berry 5,1,apple 1,2,golden,31
+    /// arena 9x4
+    /// ╭───────╮
+    /// │       │
+    /// │       │
+    /// ╰───────╯
+    /// ```
+    pub fn to_text(state: &GameState) -> Result<String, SnakeError> {
+        let (min_x, min_y) = Arena::min_arena_coords(&state.arena)?;
+        let (width, height) = Arena::size(&state.arena)?;
+
+        let relative = |c: &Coords| match c.is_active() {
+            true => format!("{},{}", c.x - min_x as i16, c.y - min_y as i16),
+            false => String::from("-"),
+        };
+
+        let turns: Vec<&str> = state.snake.pending_turns.iter().map(|d| d.name()).collect();
+        let snake: Vec<String> = state
+            .snake
+            .positions
+            .iter()
+            .map(|p| {
+                format!(
+                    "{},{},{}",
+                    relative(&p.coords),
+                    p.facing.name(),
+                    p.active as u8
+                )
+            })
+            .collect();
+        let food: Vec<String> = std::iter::once(state.food.count.to_string())
+            .chain(state.food.items.iter().map(|f| match f.ticks_left {
+                Some(t) => format!("{},{},{}", relative(&f.coords), f.kind.name(), t),
+                None => format!("{},{}", relative(&f.coords), f.kind.name()),
+            }))
+            .collect();
+
+        let mut rows = vec![vec![' '; width as usize]; height as usize];
+
+        for (coords, glyph) in &state.arena.positions {
+            let (x, y) = coords.to_unsigned_tuple();
+            rows[(y - min_y) as usize][(x - min_x) as usize] = glyph.to_char();
+        }
+
+        let mut text = format!(
+            "version {}\ndifficulty {}\nmode {}\nlevel {}\ncampaign {}\ngoal {}\nspawn {}\nstart {}\nelapsed {}\nbonus {}\nrng {}\nscore {}\ndirection {}\nturns {}\nsnake {}\nfood {}\narena {}x{}\n",
+            FILE.version(),
+            state.difficulty.name(),
+            state.mode.name(),
+            state.level.as_deref().unwrap_or("-"),
+            state.campaign.map_or(String::from("-"), |c| c.to_string()),
+            state.goal.map_or(String::from("-"), |g| g.to_string()),
+            state.spawn.map_or(String::from("-"), |c| relative(&c)),
+            state.start_score,
+            state.elapsed.as_millis(),
+            if state.food.bonus { "on" } else { "off" },
+            state.rng,
+            state.score,
+            state.snake.direction.name(),
+            turns.join(" "),
+            snake.join(" "),
+            food.join(" "),
+            width,
+            height
+        );
+
+        for row in rows {
+            text.push_str(row.iter().collect::<String>().trim_end());
+            text.push('\n');
+        }
+
+        Ok(text)
+    }
+
+    /// Reads a game written by `to_text`. The arena is put back with its top
+    /// left corner just below the score, ready to be laid out.
+    pub fn parse(text: &str) -> Result<GameState, SnakeError> {
+        let mut state = GameState::new();
+        let mut lines = text.lines().enumerate();
+
+        let mut next = |key: &str| -> Result<(usize, String), SnakeError> {
+            let (i, line) = lines
+                .next()
+                .ok_or_else(|| SnakeError::new(&format!("missing `{}`", key)))?;
+
+            match line.split_once(' ').unwrap_or((line, "")) {
+                (found, value) if found == key => Ok((i + 1, value.trim().to_string())),
+                (found, _) => Err(SnakeError::new(&format!(
+                    "line {}: expected `{}`, found '{}'",
+                    i + 1,
+                    key,
+                    found
+                ))),
+            }
+        };
+
+        let error = |line_no: usize, message: String| {
+            SnakeError::new(&format!("line {}: {}", line_no, message))
+        };
+
+        let (n, version) = next("version")?;
+        FILE.check_version(&format!("version {}", version))
+            .map_err(|e| error(n, e))?;
+
+        let (n, difficulty) = next("difficulty")?;
+        state.difficulty = Difficulty::from_name(&difficulty)
+            .ok_or_else(|| error(n, format!("unknown difficulty '{}'", difficulty)))?;
+
+        let (n, mode) = next("mode")?;
+        state.mode =
+            Mode::from_name(&mode).ok_or_else(|| error(n, format!("unknown mode '{}'", mode)))?;
+
+        let (_, level) = next("level")?;
+        state.level = Some(level).filter(|l| l != "-");
+
+        let (n, campaign) = next("campaign")?;
+        state.campaign = match campaign.as_str() {
+            "-" => None,
+            c => Some(
+                c.parse()
+                    .map_err(|_| error(n, format!("'{}' is not a campaign level", c)))?,
+            ),
+        };
+
+        let coords = |n: usize, x: &str, y: &str| -> Result<Coords, SnakeError> {
+            match (x.parse::<i16>(), y.parse::<i16>()) {
+                (Ok(x), Ok(y)) if x >= 0 && y >= 0 => {
+                    Ok(Coords::new(x + Arena::ORIGIN.0, y + Arena::ORIGIN.1))
+                }
+                _ => Err(error(n, format!("'{},{}' is not a position", x, y))),
+            }
+        };
+
+        let (n, goal) = next("goal")?;
+        state.goal = match goal.as_str() {
+            "-" => None,
+            g => Some(g.parse().map_err(|e| error(n, format!("{}", e)))?),
+        };
+
+        let (n, spawn) = next("spawn")?;
+        state.spawn = match spawn.split_once(',') {
+            None if spawn == "-" => None,
+            Some((x, y)) => Some(coords(n, x, y)?),
+            None => return Err(error(n, format!("'{}' is not a position", spawn))),
+        };
+
+        let (n, start) = next("start")?;
+        state.start_score = start
+            .parse()
+            .map_err(|_| error(n, format!("'{}' is not a score", start)))?;
+
+        let (n, elapsed) = next("elapsed")?;
+        state.elapsed = elapsed
+            .parse()
+            .map(Duration::from_millis)
+            .map_err(|_| error(n, format!("'{}' is not a number of milliseconds", elapsed)))?;
+
+        let (n, bonus) = next("bonus")?;
+        state.food.bonus = match bonus.as_str() {
+            "on" => true,
+            "off" => false,
+            _ => return Err(error(n, format!("'{}' is not on or off", bonus))),
+        };
+
+        let (n, rng) = next("rng")?;
+        state.rng = rng.parse().map_err(|e| error(n, format!("{}", e)))?;
+
+        let (n, score) = next("score")?;
+        state.score = score
+            .parse()
+            .map_err(|_| error(n, format!("'{}' is not a score", score)))?;
+
+        let direction = |n: usize, name: &str| {
+            Directions::from_name(name)
+                .ok_or_else(|| error(n, format!("unknown direction '{}'", name)))
+        };
+
+        let (n, name) = next("direction")?;
+        state.snake.direction = direction(n, &name)?;
+
+        let (n, turns) = next("turns")?;
+        for name in turns.split_whitespace() {
+            state.snake.queue_turn(direction(n, name)?);
+        }
+
+        let (n, snake) = next("snake")?;
+        state.snake.positions = snake
+            .split_whitespace()
+            .map(|block| {
+                let parts: Vec<&str> = block.split(',').collect();
+
+                let (at, facing, active) = match parts.as_slice() {
+                    ["-", facing, active] => (Coords::default(), *facing, *active),
+                    [x, y, facing, active] => (coords(n, x, y)?, *facing, *active),
+                    _ => return Err(error(n, format!("'{}' is not a snake block", block))),
+                };
+
+                Ok(SnakeCoords {
+                    coords: at,
+                    facing: direction(n, facing)?,
+                    active: active == "1",
+                })
+            })
+            .collect::<Result<Vec<SnakeCoords>, SnakeError>>()?;
+
+        if state.snake.positions.is_empty() {
+            return Err(error(n, String::from("the snake has no blocks")));
+        }
+
+        let (n, food) = next("food")?;
+        let mut food = food.split_whitespace();
+
+        state.food.count = match food.next().map(str::parse::<usize>) {
+            Some(Ok(count)) => count,
+            _ => return Err(error(n, String::from("expected how much food to keep out"))),
+        };
+
+        let food_kind = |n: usize, name: &str| {
+            FoodKind::from_name(name).ok_or_else(|| error(n, format!("unknown food '{}'", name)))
+        };
+
+        state.food.items = food
+            .map(
+                |item| match item.split(',').collect::<Vec<&str>>().as_slice() {
+                    [x, y, kind] => Ok(FoodItem {
+                        coords: coords(n, x, y)?,
+                        kind: food_kind(n, kind)?,
+                        ticks_left: None,
+                    }),
+                    [x, y, kind, ticks] => Ok(FoodItem {
+                        coords: coords(n, x, y)?,
+                        kind: food_kind(n, kind)?,
+                        ticks_left: match ticks.parse::<u16>() {
+                            Ok(t) if t > 0 => Some(t),
+                            _ => {
+                                return Err(error(
+                                    n,
+                                    format!("'{}' is not a number of steps", ticks),
+                                ))
+                            }
+                        },
+                    }),
+                    _ => Err(error(n, format!("'{}' is not a piece of food", item))),
+                },
+            )
+            .collect::<Result<Vec<FoodItem>, SnakeError>>()?;
+
+        let (n, size) = next("arena")?;
+        let (width, height) = size
+            .split_once('x')
+            .and_then(|(w, h)| Some((w.parse::<u16>().ok()?, h.parse::<u16>().ok()?)))
+            .ok_or_else(|| error(n, format!("'{}' is not an arena size", size)))?;
+
+        let rows: Vec<(usize, &str)> = text.lines().enumerate().skip(n).collect();
+
+        if rows.len() != height as usize {
+            return Err(error(
+                n,
+                format!(
+                    "the arena should be {} rows high but {} follow",
+                    height,
+                    rows.len()
+                ),
+            ));
+        }
+
+        for (y, (i, row)) in rows.into_iter().enumerate() {
+            for (x, c) in row.chars().enumerate() {
+                if x >= width as usize {
+                    return Err(error(
+                        i + 1,
+                        format!("the arena should be {} columns wide", width),
+                    ));
+                }
+
+                if c == ' ' {
+                    continue;
+                }
+
+                let glyph = Unicode::from_char(c).ok_or_else(|| {
+                    error(i + 1, format!("unknown wall '{}' in column {}", c, x + 1))
+                })?;
+
+                state.arena.positions.push((
+                    Coords::new(x as i16 + Arena::ORIGIN.0, y as i16 + Arena::ORIGIN.1),
+                    glyph,
+                ));
+            }
+        }
+
+        // Everything has to be inside the arena it was saved with.
+        if Arena::size(&state.arena)? != (width, height) {
+            return Err(SnakeError::new(&format!(
+                "the walls don't make a {}x{} arena",
+                width, height
+            )));
+        }
+
+        let (max_x, max_y) = Arena::max_arena_coords(&state.arena)?;
+        let outside = state
+            .snake
+            .positions
+            .iter()
+            .map(|p| p.coords)
+            .chain(state.food.items.iter().map(|f| f.coords))
+            .chain(state.spawn)
+            .filter(|c| c.is_active())
+            .any(|c| c.x > max_x as i16 || c.y > max_y as i16);
+
+        if outside {
+            return Err(SnakeError::new(&format!(
+                "the snake, food or spawn is outside the {}x{} arena",
+                width, height
+            )));
+        }
+
+        state.sync_board();
+
+        Ok(state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{random::random::Random, state::goal::Goal};
+
+    use super::*;
+
+    //
+    // Snake turning from Right to Down with one queued turn and two pieces
+    // of food, part way through a hard game.
+    //
+    fn set_state() -> GameState {
+        let mut state = GameState::turning_snake();
+
+        state.score = 3;
+        state.difficulty = Difficulty::Hard;
+        state.rng = Random::new(42);
+        state.snake.pending_turns.push_back(Directions::Left);
+        state.food.items = vec![
+            FoodItem::new(Coords::new(7, 5), FoodKind::Apple),
+            FoodItem::new(Coords::new(2, 4), FoodKind::Golden),
+        ];
+        state.food.items[1].ticks_left = Some(12);
+
+        state
+    }
+
+    const TEXT: &str = "version 8
+difficulty hard
+mode classic
+level -
+campaign -
+goal -
+spawn -
+start 0
+elapsed 0
+bonus on
+rng lcg 42
+score 3
+direction down
+turns left
+snake 4,2,down,1 4,1,right,1 3,1,right,1
+food 3 6,2,apple 1,1,golden,12
+arena 9x4
+╭───────╮
+│       │
+│       │
+╰───────╯
+";
+
+    #[test]
+    fn to_text_snapshot() {
+        assert_eq!(SaveGame::to_text(&set_state()).unwrap(), TEXT);
+    }
+
+    #[test]
+    fn parse_round_trips() {
+        let state = SaveGame::parse(TEXT).unwrap();
+
+        assert_eq!(SaveGame::to_text(&state).unwrap(), TEXT);
+        assert_eq!(state.snake.positions[0].coords, Coords::new(5, 5));
+        assert_eq!(state.food.count, 3);
+        assert_eq!(state.food.items, set_state().food.items);
+        assert_eq!(Arena::min_arena_coords(&state.arena), Ok((1, 3)));
+        assert_eq!(state.snake.next_direction(), Directions::Left);
+        assert_eq!(state.rng, Random::new(42));
+    }
+
+    #[test]
+    fn parse_round_trips_level_rules() {
+        let text = TEXT
+            .replace("level -", "level Walled garden")
+            .replace("campaign -", "campaign 2")
+            .replace("goal -", "goal survive 30")
+            .replace("spawn -", "spawn 2,1")
+            .replace("start 0", "start 12")
+            .replace("elapsed 0", "elapsed 4250")
+            .replace("bonus on", "bonus off");
+        let state = SaveGame::parse(&text).unwrap();
+
+        assert_eq!(state.level.as_deref(), Some("Walled garden"));
+        assert_eq!(state.campaign, Some(2));
+        assert_eq!(state.goal, Some(Goal::Survive(30)));
+        assert_eq!(state.spawn, Some(Coords::new(3, 4)));
+        assert_eq!(state.start_score, 12);
+        assert_eq!(state.elapsed, Duration::from_millis(4250));
+        assert!(!state.food.bonus);
+        assert_eq!(SaveGame::to_text(&state).unwrap(), text);
+    }
+
+    #[test]
+    fn parse_rejects_other_versions() {
+        let text = TEXT.replace("version 8", "version 7");
+
+        assert_eq!(
+            SaveGame::parse(&text).err(),
+            Some(SnakeError::new(
+                "line 1: this file is version 7 but only version 8 can be loaded"
+            ))
+        );
+    }
+
+    #[test]
+    fn parse_rejects_mismatched_arena() {
+        let check = |from: &str, to: &str, message: &str| {
+            let text = TEXT.replace(from, to);
+            assert_eq!(SaveGame::parse(&text).err(), Some(SnakeError::new(message)));
+        };
+
+        check(
+            "arena 9x4",
+            "arena 9x5",
+            "line 17: the arena should be 5 rows high but 4 follow",
+        );
+        check(
+            "arena 9x4",
+            "arena 8x4",
+            "line 18: the arena should be 8 columns wide",
+        );
+        check(
+            "food 3 6,2",
+            "food 3 9,2",
+            "the snake, food or spawn is outside the 9x4 arena",
+        );
+        check(
+            "spawn -",
+            "spawn 9,1",
+            "the snake, food or spawn is outside the 9x4 arena",
+        );
+        check("1,1,golden", "1,1,pear", "line 16: unknown food 'pear'");
+        check(
+            "golden,12",
+            "golden,0",
+            "line 16: '0' is not a number of steps",
+        );
+        check(
+            "╰───────╯",
+            "╰───x───╯",
+            "line 21: unknown wall 'x' in column 5",
+        );
+    }
+
+    #[test]
+    fn parse_errors_name_the_missing_field() {
+        assert_eq!(
+            SaveGame::parse("version 8\nmode classic").err(),
+            Some(SnakeError::new(
+                "line 2: expected `difficulty`, found 'mode'"
+            ))
+        );
+        assert_eq!(
+            SaveGame::parse("version 8\ndifficulty easy").err(),
+            Some(SnakeError::new("missing `mode`"))
+        );
+    }
+}
@@ -1,9 +1,10 @@
-use std::error::Error;
-
-use crate::engine::{graphics::Graphics, unicode::Unicode};
+use std::collections::VecDeque;
 
 use super::{directions::Directions, snake_coords::SnakeCoords};
 
+// How many turns can be waiting for upcoming ticks at once.
+const MAX_PENDING_TURNS: usize = 3;
+
 #[derive(Debug)]
 pub struct Snake {
     // The position of each block making up the body of snake
@@ -16,6 +17,9 @@ pub struct Snake {
     // Holds the direction snake's head is currently facing
     pub direction: Directions,
 
+    // Turns waiting to be taken, one per step, oldest first
+    pub pending_turns: VecDeque<Directions>,
+
     // Snake is dead x_x
     pub x_x: bool,
 }
@@ -25,18 +29,46 @@ impl Snake {
         Snake {
             positions: vec![SnakeCoords::default()],
             direction: Directions::None,
+            pending_turns: VecDeque::new(),
             x_x: false,
         }
     }
 
     //
-    // Steps snake one block in the direction he is facing.
+    // Queues a turn to be taken on a later step. Returns false, dropping the
+    // turn, if the queue is already full.
+    //
+    pub fn queue_turn(&mut self, direction: Directions) -> bool {
+        if self.pending_turns.len() >= MAX_PENDING_TURNS {
+            return false;
+        }
+
+        self.pending_turns.push_back(direction);
+
+        true
+    }
+
+    //
+    // The direction snake will be facing once every queued turn is taken.
+    //
+    pub fn next_direction(&self) -> Directions {
+        self.pending_turns.back().copied().unwrap_or(self.direction)
+    }
+
+    //
+    // Steps snake one block in the direction he is facing, after taking the
+    // next queued turn.
     // Effectively this code is removing the tail block, shifting all elements
     // in the array down one and adds a new head (0) block at the shifted
     // coordinates. This algorithm will be used to draw the snake on every
     // tick to make it look like it's moving.
     //
     pub fn step(&mut self) -> &mut Snake {
+        // Take the next queued turn, if there is one.
+        if let Some(direction) = self.pending_turns.pop_front() {
+            self.direction = direction;
+        }
+
         let current_head = self.positions[0];
         //let mut new_positions: [SnakeCoords; 20] = [SnakeCoords::default(); 20];
 
@@ -88,7 +120,8 @@ impl Snake {
             // for the new one.
             let previous_tail = self.positions[a + 1 - 1];
 
-            let mut new_position = SnakeCoords::new(1, 1, previous_tail.facing, previous_tail.active);
+            let mut new_position =
+                SnakeCoords::new(1, 1, previous_tail.facing, previous_tail.active);
 
             match previous_tail.facing {
                 Directions::Up => {
@@ -117,22 +150,14 @@ impl Snake {
     }
 
     //
-    // Loops through each snake position and draws a space to clear it.
+    // Takes the given number of blocks (amount) off snake's tail, always
+    // leaving him his head.
     //
-    pub fn clear(&self) -> Result<(), Box<dyn Error>> {
-        for (i, p) in self.positions.iter().enumerate() {
-            // Clear all positions that don't have a facing or have and invalid position.
-            // Always clear i when i is 0 as we want to make sure the starting piece is cleared.
-            if p.facing == Directions::None && !p.active && i > 0 {
-                continue;
-            }
+    pub fn shrink(&mut self, amount: usize) -> &mut Snake {
+        let length = self.positions.len().saturating_sub(amount).max(1);
+        self.positions.truncate(length);
 
-            let (ux, uy) = p.coords.to_unsigned_tuple();
-
-            Graphics::draw_char(ux, uy, Unicode::Space)?;
-        }
-
-        Ok(())
+        self
     }
 
     //
@@ -156,18 +181,6 @@ impl Snake {
     pub fn is_active(&self) -> bool {
         self.active_length().is_some()
     }
-
-    //
-    // Returns true if Snake's head block has hit any part of his body.
-    //
-    pub fn has_hit_self(&self) -> bool {
-        // Skip the head piece, otherwise this always returns true.
-        let exists = self.positions[1..]
-            .iter()
-            .any(|p| p.coords == self.positions[0].coords);
-
-        exists && self.positions.len() > 1
-    }
 }
 
 impl Default for Snake {
@@ -193,6 +206,7 @@ mod tests {
         let mut snake = Snake {
             positions: vec![SnakeCoords::new(-1, -1, Directions::None, false)],
             direction: Directions::Up,
+            pending_turns: VecDeque::new(),
             x_x: false,
         };
 
@@ -221,6 +235,7 @@ mod tests {
         let mut snake = Snake {
             positions: Vec::new(),
             direction: Directions::Up,
+            pending_turns: VecDeque::new(),
             x_x: false,
         };
 
@@ -253,6 +268,7 @@ mod tests {
         let mut snake = Snake {
             positions: vec![SnakeCoords::new(10, 10, direction, true)],
             direction: direction,
+            pending_turns: VecDeque::new(),
             x_x: false,
         };
 
@@ -347,34 +363,65 @@ mod tests {
     }
 
     //
-    // If the head block intersects any of the body, has_hit_self should return true.
+    // Shrinking takes blocks off the tail but never the head.
     //
     #[test]
-    fn has_hit_self_return_true() {
-        let mut snake = Snake::new();
-        snake.direction = Directions::Down;
-        snake.positions = vec![SnakeCoords::new(5, 10, Directions::Down, true)];
+    fn shrink_keeps_the_head() {
+        let mut snake = set_snake_and_grow(Directions::Right, 4);
 
-        snake.grow(5);
+        snake.shrink(2);
+        assert_eq!(snake.positions.len(), 3);
+        assert_eq!(snake.positions[2].coords, Coords::new(8, 10));
 
-        snake.positions[0].coords = Coords::new(5, 7);
-
-        assert_eq!(true, Snake::has_hit_self(&snake));
+        snake.shrink(10);
+        assert_eq!(snake.positions.len(), 1);
+        assert_eq!(snake.positions[0].coords, Coords::new(10, 10));
     }
 
     //
-    // Make sure has_hit_self returns false if the head does not intersect the body
+    // Queued turns are taken one per step, in order.
     //
     #[test]
-    fn has_hit_self_return_false() {
+    fn step_takes_one_queued_turn() {
         let mut snake = Snake::new();
-        snake.direction = Directions::Down;
-        snake.positions = vec![SnakeCoords::new(5, 10, Directions::Down, true)];
+        snake.direction = Directions::Right;
+        snake.positions = vec![SnakeCoords::new(5, 5, Directions::Right, true)];
+
+        snake.queue_turn(Directions::Up);
+        snake.queue_turn(Directions::Left);
+
+        snake.step();
+        assert_eq!(snake.direction, Directions::Up);
+        assert_eq!(snake.positions[0].coords, Coords::new(5, 4));
 
-        snake.grow(5);
+        snake.step();
+        assert_eq!(snake.direction, Directions::Left);
+        assert_eq!(snake.positions[0].coords, Coords::new(4, 4));
 
-        snake.positions[0].coords = Coords::new(5, 11);
+        snake.step();
+        assert_eq!(snake.direction, Directions::Left);
+        assert!(snake.pending_turns.is_empty());
+    }
+
+    #[test]
+    fn queue_turn_is_bounded() {
+        let mut snake = Snake::new();
+
+        for _ in 0..MAX_PENDING_TURNS {
+            assert!(snake.queue_turn(Directions::Up));
+        }
+
+        assert!(!snake.queue_turn(Directions::Up));
+        assert_eq!(snake.pending_turns.len(), MAX_PENDING_TURNS);
+    }
+
+    #[test]
+    fn next_direction_is_last_queued_turn() {
+        let mut snake = Snake::new();
+        snake.direction = Directions::Right;
+        assert_eq!(snake.next_direction(), Directions::Right);
 
-        assert_eq!(false, Snake::has_hit_self(&snake));
+        snake.queue_turn(Directions::Down);
+        assert_eq!(snake.next_direction(), Directions::Down);
     }
 }
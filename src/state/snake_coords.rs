@@ -14,7 +14,7 @@ impl SnakeCoords {
         SnakeCoords {
             coords: Coords::new(x, y),
             facing,
-            active: active,
+            active,
         }
     }
 }
@@ -0,0 +1,57 @@
+use crate::engine::coords::Coords;
+
+use super::food::FoodItem;
+
+/// What snake ran into that killed him.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Death {
+    /// The arena's outer edge.
+    Wall,
+    /// A wall inside the arena.
+    Obstacle,
+    /// His own body.
+    Himself,
+}
+
+/// Something that happened during a tick, in the order it happened.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TickEvent {
+    /// Snake was put in the arena at the start of the game.
+    Placed(Coords),
+    /// Snake's head moved to the given cell.
+    Moved(Coords),
+    Ate(FoodItem),
+    /// Snake got this many blocks longer.
+    Grew(usize),
+    /// Snake lost this many blocks off his tail.
+    Shrank(usize),
+    /// Food that wasn't eaten in time and has gone.
+    Expired(FoodItem),
+    Died(Death),
+    /// The arena is full or the level's goal was reached.
+    Won,
+}
+
+/// Everything that happened during a tick of the game.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TickOutcome {
+    pub events: Vec<TickEvent>,
+}
+
+impl TickOutcome {
+    pub fn push(&mut self, event: TickEvent) {
+        self.events.push(event);
+    }
+
+    /// What killed snake, if he died.
+    pub fn died(&self) -> Option<Death> {
+        self.events.iter().find_map(|e| match e {
+            TickEvent::Died(death) => Some(*death),
+            _ => None,
+        })
+    }
+
+    pub fn won(&self) -> bool {
+        self.events.contains(&TickEvent::Won)
+    }
+}
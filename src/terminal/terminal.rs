@@ -1,8 +1,14 @@
 use libc::{ioctl, tcgetattr, tcsetattr, termios, winsize, STDIN_FILENO, TCSANOW, TIOCGWINSZ};
 use std::fs::File;
-use std::mem;
+use std::io::Write;
+use std::mem::{self, ManuallyDrop};
 use std::os::unix::io::AsRawFd;
 use std::os::unix::io::FromRawFd;
+use std::panic;
+use std::sync::atomic::{AtomicBool, AtomicI32, Ordering};
+use std::sync::{Once, OnceLock};
+
+use crate::engine::snengine_error::SnengineError;
 
 pub struct Terminal {}
 
@@ -59,3 +65,172 @@ impl Terminal {
         (ws.ws_col, ws.ws_row)
     }
 }
+
+// Escape sequences for the terminal features a session turns on and off.
+const ENTER_ALTERNATE_SCREEN: &str = "\x1b[?1049h";
+const LEAVE_ALTERNATE_SCREEN: &str = "\x1b[?1049l";
+const HIDE_CURSOR: &str = "\x1b[?25l";
+const SHOW_CURSOR: &str = "\x1b[?25h";
+
+// The state to go back to is kept in statics rather than on the session so
+// the panic hook and signal handlers can restore it too.
+static ORIGINAL_TERM: OnceLock<termios> = OnceLock::new();
+static ORIGINAL_FLAGS: AtomicI32 = AtomicI32::new(0);
+static SESSION_ACTIVE: AtomicBool = AtomicBool::new(false);
+
+// Set by the SIGWINCH handler and picked up by `TerminalSession::poll_event`.
+static RESIZED: AtomicBool = AtomicBool::new(false);
+
+/// Something that happened to the terminal itself, as opposed to key input.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TerminalEvent {
+    Resized { cols: u16, rows: u16 },
+}
+
+/// Owns the terminal for the lifetime of the game: raw mode, non-blocking
+/// stdin, a hidden cursor and the alternate screen.
+///
+/// Everything is put back when the session is dropped, when the program
+/// panics, and when it receives SIGINT, SIGTERM or SIGHUP.
+pub struct TerminalSession {
+    input: ManuallyDrop<File>,
+}
+
+impl TerminalSession {
+    pub fn start() -> Result<Self, SnengineError> {
+        if SESSION_ACTIVE.load(Ordering::SeqCst) {
+            return Err(SnengineError::new("A terminal session is already active"));
+        }
+
+        let mut term = unsafe { mem::zeroed::<termios>() };
+
+        if unsafe { tcgetattr(STDIN_FILENO, &mut term) } != 0 {
+            return Err(SnengineError::new("stdin is not a terminal"));
+        }
+
+        let _ = ORIGINAL_TERM.set(term);
+        ORIGINAL_FLAGS.store(
+            unsafe { libc::fcntl(STDIN_FILENO, libc::F_GETFL, 0) },
+            Ordering::SeqCst,
+        );
+
+        TerminalSession::install_panic_hook();
+        TerminalSession::install_signal_handlers();
+
+        SESSION_ACTIVE.store(true, Ordering::SeqCst);
+
+        Terminal::set_raw_mode();
+        let input = Terminal::set_non_blocking_stdin();
+
+        print!("{}{}", ENTER_ALTERNATE_SCREEN, HIDE_CURSOR);
+        let _ = std::io::stdout().flush();
+
+        Ok(TerminalSession {
+            input: ManuallyDrop::new(input),
+        })
+    }
+
+    /// The non-blocking stdin handle to read input from.
+    pub fn input(&self) -> &File {
+        &self.input
+    }
+
+    /// Returns the next pending terminal event, if any. Resizes which happen
+    /// in quick succession are collapsed into one event with the latest size.
+    pub fn poll_event(&self) -> Option<TerminalEvent> {
+        if !RESIZED.swap(false, Ordering::SeqCst) {
+            return None;
+        }
+
+        let (cols, rows) = Terminal::get_console_size();
+
+        Some(TerminalEvent::Resized { cols, rows })
+    }
+
+    /// Puts the terminal back the way it was before the session started.
+    /// Only uses async-signal-safe calls so it can run from a signal handler,
+    /// and does nothing if there's no active session.
+    fn restore() {
+        if !SESSION_ACTIVE.swap(false, Ordering::SeqCst) {
+            return;
+        }
+
+        unsafe {
+            if let Some(term) = ORIGINAL_TERM.get() {
+                tcsetattr(STDIN_FILENO, TCSANOW, term);
+            }
+
+            libc::fcntl(
+                STDIN_FILENO,
+                libc::F_SETFL,
+                ORIGINAL_FLAGS.load(Ordering::SeqCst),
+            );
+
+            for reset in [SHOW_CURSOR, LEAVE_ALTERNATE_SCREEN] {
+                libc::write(
+                    libc::STDOUT_FILENO,
+                    reset.as_ptr() as *const libc::c_void,
+                    reset.len(),
+                );
+            }
+        }
+    }
+
+    fn install_panic_hook() {
+        static INSTALLED: Once = Once::new();
+
+        INSTALLED.call_once(|| {
+            let previous = panic::take_hook();
+
+            panic::set_hook(Box::new(move |info| {
+                // Restore first so the panic message lands on the normal screen.
+                TerminalSession::restore();
+                previous(info);
+            }));
+        });
+    }
+
+    fn install_signal_handlers() {
+        for signal in [libc::SIGINT, libc::SIGTERM, libc::SIGHUP] {
+            unsafe {
+                libc::signal(
+                    signal,
+                    handle_termination_signal as *const () as libc::sighandler_t,
+                );
+            }
+        }
+
+        unsafe {
+            libc::signal(
+                libc::SIGWINCH,
+                handle_resize_signal as *const () as libc::sighandler_t,
+            );
+        }
+    }
+}
+
+impl Drop for TerminalSession {
+    fn drop(&mut self) {
+        // Flush anything still buffered before leaving the alternate screen.
+        let _ = std::io::stdout().flush();
+
+        TerminalSession::restore();
+    }
+}
+
+//
+// Restores the terminal, then re-raises the signal with the default handler
+// so the process still exits with the status the sender expects.
+//
+extern "C" fn handle_termination_signal(signal: libc::c_int) {
+    TerminalSession::restore();
+
+    unsafe {
+        libc::signal(signal, libc::SIG_DFL);
+        libc::raise(signal);
+    }
+}
+
+extern "C" fn handle_resize_signal(_signal: libc::c_int) {
+    RESIZED.store(true, Ordering::SeqCst);
+}